@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::passport::{parse_input, FieldType, Passport, PassportField};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Format {
+    /// the blank-line-separated `key:value` format of the puzzle input
+    Text,
+    /// one JSON object per line
+    Json,
+    /// one header row with all field keys, one row per passport
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format = match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            _ => return Err(()),
+        };
+
+        Ok(format)
+    }
+}
+
+#[derive(Debug)]
+pub enum ConvertError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Csv(csv::Error),
+    UnknownField(String),
+    EmptyField(FieldType),
+    MalformedField(String),
+    DuplicateField(FieldType),
+    /// a value the text format can't hold because it contains whitespace
+    UnwritableField(FieldType),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Io(e) => write!(f, "io error: {}", e),
+            ConvertError::Json(e) => write!(f, "json error: {}", e),
+            ConvertError::Csv(e) => write!(f, "csv error: {}", e),
+            ConvertError::UnknownField(key) => write!(f, "unknown field '{}'", key),
+            ConvertError::EmptyField(field_type) => write!(f, "empty field '{}'", field_type),
+            ConvertError::MalformedField(field) => {
+                write!(f, "expected 'key:value' but got '{}'", field)
+            }
+            ConvertError::DuplicateField(field_type) => {
+                write!(f, "field '{}' appears more than once", field_type)
+            }
+            ConvertError::UnwritableField(field_type) => write!(
+                f,
+                "field '{}' contains whitespace and can't be written as text",
+                field_type
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for ConvertError {
+    fn from(e: io::Error) -> Self {
+        ConvertError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ConvertError {
+    fn from(e: serde_json::Error) -> Self {
+        ConvertError::Json(e)
+    }
+}
+
+impl From<csv::Error> for ConvertError {
+    fn from(e: csv::Error) -> Self {
+        ConvertError::Csv(e)
+    }
+}
+
+fn parse_field(key: &str, value: &str) -> Result<PassportField, ConvertError> {
+    let field_type =
+        FieldType::from_str(key).map_err(|_| ConvertError::UnknownField(String::from(key)))?;
+    if value.is_empty() {
+        return Err(ConvertError::EmptyField(field_type));
+    }
+
+    Ok(PassportField::new(key, value))
+}
+
+/// a passport from `key`/`value` pairs, every key may appear only once
+pub(crate) fn passport_from_pairs<'a, I>(pairs: I) -> Result<Passport, ConvertError>
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let mut fields = HashMap::new();
+
+    for (key, value) in pairs {
+        let field = parse_field(key, value)?;
+        if fields.contains_key(&field.field_type) {
+            return Err(ConvertError::DuplicateField(field.field_type));
+        }
+        fields.insert(field.field_type, field);
+    }
+
+    Ok(Passport { fields })
+}
+
+impl Serialize for Passport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in self.ordered_fields() {
+            map.serialize_entry(field.field_type.key(), &field.field)?;
+        }
+        map.end()
+    }
+}
+
+struct PassportVisitor;
+
+impl<'de> Visitor<'de> for PassportVisitor {
+    type Value = Passport;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a map of passport fields")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        // every entry is kept so a repeated key is an error and not overwritten
+        let mut pairs = Vec::new();
+        while let Some(pair) = map.next_entry::<String, String>()? {
+            pairs.push(pair);
+        }
+
+        passport_from_pairs(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Passport {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(PassportVisitor)
+    }
+}
+
+fn read_json<R: BufRead>(reader: R) -> Result<Vec<Passport>, ConvertError> {
+    let mut result = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        result.push(serde_json::from_str(&line)?);
+    }
+
+    Ok(result)
+}
+
+fn read_csv<R: BufRead>(reader: R) -> Result<Vec<Passport>, ConvertError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+
+    let mut result = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        // an empty cell means the passport lacks that field
+        let pairs = headers
+            .iter()
+            .zip(record.iter())
+            .filter(|(_, value)| !value.is_empty());
+
+        result.push(passport_from_pairs(pairs)?);
+    }
+
+    Ok(result)
+}
+
+pub fn read_passports<R: BufRead>(
+    reader: R,
+    format: Format,
+) -> Result<Vec<Passport>, ConvertError> {
+    match format {
+        Format::Text => parse_input(reader).collect(),
+        Format::Json => read_json(reader),
+        Format::Csv => read_csv(reader),
+    }
+}
+
+fn write_text<W: Write>(mut writer: W, passports: &[Passport]) -> Result<(), ConvertError> {
+    for (i, passport) in passports.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+
+        // the fields are separated by whitespace, so it can't be in a value
+        let fields = passport
+            .ordered_fields()
+            .map(|f| {
+                if f.field.contains(char::is_whitespace) {
+                    Err(ConvertError::UnwritableField(f.field_type))
+                } else {
+                    Ok(format!("{}:{}", f.field_type, f.field))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        writeln!(writer, "{}", fields.join(" "))?;
+    }
+
+    Ok(())
+}
+
+fn write_json<W: Write>(mut writer: W, passports: &[Passport]) -> Result<(), ConvertError> {
+    for passport in passports {
+        serde_json::to_writer(&mut writer, passport)?;
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+fn write_csv<W: Write>(writer: W, passports: &[Passport]) -> Result<(), ConvertError> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(FieldType::ALL.iter().map(FieldType::key))?;
+
+    for passport in passports {
+        let record = FieldType::ALL.iter().map(|field_type| {
+            passport
+                .fields
+                .get(field_type)
+                .map_or("", |f| f.field.as_str())
+        });
+        writer.write_record(record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_passports<W: Write>(
+    writer: W,
+    passports: &[Passport],
+    format: Format,
+) -> Result<(), ConvertError> {
+    match format {
+        Format::Text => write_text(writer, passports),
+        Format::Json => write_json(writer, passports),
+        Format::Csv => write_csv(writer, passports),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:grn pid:087499704
+
+byr:1989 iyr:2014 hcl:#a97842 ecl:blu pid:896056539 cid:129
+";
+
+    fn convert(input: &str, from: Format, to: Format) -> String {
+        let passports = read_passports(input.as_bytes(), from).unwrap();
+        let mut output = Vec::new();
+        write_passports(&mut output, &passports, to).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = convert(TEXT, Format::Text, Format::Json);
+        assert_eq!(
+            "{\"byr\":\"1980\",\"iyr\":\"2012\",\"eyr\":\"2030\",\"hgt\":\"74in\",\
+             \"hcl\":\"#623a2f\",\"ecl\":\"grn\",\"pid\":\"087499704\"}",
+            json.lines().next().unwrap()
+        );
+        assert_eq!(TEXT, convert(&json, Format::Json, Format::Text));
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let csv = convert(TEXT, Format::Text, Format::Csv);
+        assert_eq!(
+            "byr,iyr,eyr,hgt,hcl,ecl,pid,cid\n\
+             1980,2012,2030,74in,#623a2f,grn,087499704,\n\
+             1989,2014,,,#a97842,blu,896056539,129\n",
+            csv
+        );
+        assert_eq!(TEXT, convert(&csv, Format::Csv, Format::Text));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let result = read_passports("byr:1980 foo:bar".as_bytes(), Format::Text);
+        assert!(matches!(result, Err(ConvertError::UnknownField(k)) if k == "foo"));
+
+        let result = read_passports("byr:1980 iyr".as_bytes(), Format::Text);
+        assert!(matches!(result, Err(ConvertError::MalformedField(_))));
+
+        let result = read_passports("byr:1980 iyr:2012\nbyr:1981".as_bytes(), Format::Text);
+        assert!(matches!(
+            result,
+            Err(ConvertError::DuplicateField(FieldType::BirthYear))
+        ));
+
+        let result = read_passports("byr,byr\n1980,1981\n".as_bytes(), Format::Csv);
+        assert!(matches!(
+            result,
+            Err(ConvertError::DuplicateField(FieldType::BirthYear))
+        ));
+
+        let result = read_passports("{\"byr\":\"\"}".as_bytes(), Format::Json);
+        assert!(matches!(result, Err(ConvertError::Json(_))));
+
+        let result = read_passports(
+            "{\"byr\":\"1980\",\"byr\":\"1981\"}".as_bytes(),
+            Format::Json,
+        );
+        assert!(
+            matches!(result, Err(ConvertError::Json(e)) if e.to_string().contains("more than once"))
+        );
+    }
+
+    #[test]
+    fn test_unwritable_text() {
+        let passports = read_passports("{\"hgt\":\"74 in\"}".as_bytes(), Format::Json).unwrap();
+        let result = write_passports(Vec::new(), &passports, Format::Text);
+        assert!(matches!(
+            result,
+            Err(ConvertError::UnwritableField(FieldType::Height))
+        ));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod convert;
+pub mod passport;
+pub mod rules;
+#[cfg(test)]
+mod test_util;
+pub mod validated_passport;
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::convert::{passport_from_pairs, ConvertError};
+use crate::rules::RuleSet;
+use crate::validated_passport::{parse_passport_id, parse_year, EyeColor, Height, Rgb};
+use records::ReadRecords;
+
+pub trait Valid {
+    fn is_valid(&self) -> bool;
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum FieldType {
+    BirthYear,
+    IssueYear,
+    ExpirationYear,
+    Height,
+    HairColor,
+    EyeColor,
+    PassportID,
+    CountryID,
+}
+
+impl FromStr for FieldType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field_type = match s {
+            "byr" => FieldType::BirthYear,
+            "iyr" => FieldType::IssueYear,
+            "eyr" => FieldType::ExpirationYear,
+            "hgt" => FieldType::Height,
+            "hcl" => FieldType::HairColor,
+            "ecl" => FieldType::EyeColor,
+            "pid" => FieldType::PassportID,
+            "cid" => FieldType::CountryID,
+            _ => return Err(()),
+        };
+
+        Ok(field_type)
+    }
+}
+
+impl FieldType {
+    /// all field types in the order they are listed in the puzzle
+    pub const ALL: [FieldType; 8] = [
+        FieldType::BirthYear,
+        FieldType::IssueYear,
+        FieldType::ExpirationYear,
+        FieldType::Height,
+        FieldType::HairColor,
+        FieldType::EyeColor,
+        FieldType::PassportID,
+        FieldType::CountryID,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            FieldType::BirthYear => "byr",
+            FieldType::IssueYear => "iyr",
+            FieldType::ExpirationYear => "eyr",
+            FieldType::Height => "hgt",
+            FieldType::HairColor => "hcl",
+            FieldType::EyeColor => "ecl",
+            FieldType::PassportID => "pid",
+            FieldType::CountryID => "cid",
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+pub struct PassportField {
+    pub field_type: FieldType,
+    pub field: String,
+}
+
+impl PassportField {
+    pub fn new(field_type: &str, field: &str) -> PassportField {
+        if field_type.is_empty() || field.is_empty() {
+            panic!("empty field_type '{}' or field '{}'", field_type, field)
+        }
+        PassportField {
+            field_type: FieldType::from_str(field_type).expect("Couldn't parse field string"),
+            field: String::from(field),
+        }
+    }
+}
+
+impl Valid for PassportField {
+    fn is_valid(&self) -> bool {
+        let field = self.field.as_str();
+        match &self.field_type {
+            FieldType::EyeColor => field.parse::<EyeColor>().is_ok(),
+            FieldType::Height => field.parse::<Height>().is_ok(),
+            FieldType::PassportID => parse_passport_id(field).is_some(),
+            FieldType::CountryID => true,
+            FieldType::ExpirationYear => parse_year(field, 2020, 2030).is_some(),
+            FieldType::BirthYear => parse_year(field, 1920, 2002).is_some(),
+            FieldType::IssueYear => parse_year(field, 2010, 2020).is_some(),
+            FieldType::HairColor => field.parse::<Rgb>().is_ok(),
+        }
+    }
+}
+
+pub const NEEDED_FIELDS: &[FieldType] = &[
+    FieldType::BirthYear,
+    //FieldType::CountryID,
+    FieldType::ExpirationYear,
+    FieldType::EyeColor,
+    FieldType::HairColor,
+    FieldType::Height,
+    FieldType::IssueYear,
+    FieldType::PassportID,
+];
+
+pub struct Passport {
+    pub fields: HashMap<FieldType, PassportField>,
+}
+
+impl Passport {
+    pub fn new(input_fields: Vec<PassportField>) -> Passport {
+        let mut fields: HashMap<FieldType, PassportField> =
+            HashMap::with_capacity(input_fields.len());
+
+        for f in input_fields {
+            fields.insert(f.field_type, f);
+        }
+
+        Passport { fields }
+    }
+
+    /// fields in the order of `FieldType::ALL`
+    pub fn ordered_fields(&self) -> impl Iterator<Item = &PassportField> {
+        FieldType::ALL
+            .iter()
+            .filter_map(move |field_type| self.fields.get(field_type))
+    }
+}
+
+impl Valid for Passport {
+    fn is_valid(&self) -> bool {
+        RuleSet::default().is_valid(self)
+    }
+}
+
+fn parse_passport(record: Vec<String>) -> Result<Passport, ConvertError> {
+    let pairs = record
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .map(|field| {
+            let mut split = field.splitn(2, ':');
+            let key = split.next().unwrap_or_default();
+            let value = split
+                .next()
+                .ok_or_else(|| ConvertError::MalformedField(String::from(field)))?;
+            Ok((key, value))
+        })
+        .collect::<Result<Vec<_>, ConvertError>>()?;
+
+    passport_from_pairs(pairs)
+}
+
+/// lazily parses one passport per blank-line-separated record of `input`
+pub fn parse_input<R: BufRead>(input: R) -> impl Iterator<Item = Result<Passport, ConvertError>> {
+    input.records().map(|record| parse_passport(record?))
+}
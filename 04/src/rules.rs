@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::passport::{FieldType, Passport, PassportField, Valid, NEEDED_FIELDS};
+
+pub type Fields = HashMap<FieldType, PassportField>;
+
+/// A rule that is checked against all fields of a passport at once.
+///
+/// Rules only judge the fields they can see. Missing or malformed fields are
+/// reported by the required field set and the per-field `Valid` impl instead.
+pub trait RecordRule {
+    fn is_satisfied(&self, fields: &Fields) -> bool;
+    fn describe(&self) -> String;
+}
+
+fn year(fields: &Fields, field_type: FieldType) -> Option<u32> {
+    fields.get(&field_type)?.field.parse().ok()
+}
+
+/// the year of the first field must be strictly before the year of the second
+pub struct YearBefore(pub FieldType, pub FieldType);
+
+impl RecordRule for YearBefore {
+    fn is_satisfied(&self, fields: &Fields) -> bool {
+        match (year(fields, self.0), year(fields, self.1)) {
+            (Some(first), Some(second)) => first < second,
+            _ => true,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} must be before {}", self.0, self.1)
+    }
+}
+
+/// `field` is needed as soon as `when` holds the value `equals`
+pub struct RequiredWhen {
+    pub field: FieldType,
+    pub when: FieldType,
+    pub equals: &'static str,
+}
+
+impl RecordRule for RequiredWhen {
+    fn is_satisfied(&self, fields: &Fields) -> bool {
+        match fields.get(&self.when) {
+            Some(f) if f.field == self.equals => fields.contains_key(&self.field),
+            _ => true,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} is required when {} is {}",
+            self.field, self.when, self.equals
+        )
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DocumentType {
+    Passport,
+    /// passports without a country, see the puzzle text
+    NorthPoleCredentials,
+}
+
+impl FromStr for DocumentType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let document_type = match s {
+            "passport" => DocumentType::Passport,
+            "north-pole" => DocumentType::NorthPoleCredentials,
+            _ => return Err(()),
+        };
+
+        Ok(document_type)
+    }
+}
+
+impl DocumentType {
+    pub fn needed_fields(&self) -> &'static [FieldType] {
+        match self {
+            DocumentType::Passport => &FieldType::ALL,
+            DocumentType::NorthPoleCredentials => NEEDED_FIELDS,
+        }
+    }
+}
+
+pub struct RuleSet {
+    needed_fields: &'static [FieldType],
+    rules: Vec<Box<dyn RecordRule>>,
+}
+
+impl Default for RuleSet {
+    /// the rules of the puzzle: every field on its own, `cid` is optional
+    fn default() -> Self {
+        RuleSet::new(DocumentType::NorthPoleCredentials)
+    }
+}
+
+impl RuleSet {
+    pub fn new(document_type: DocumentType) -> RuleSet {
+        RuleSet {
+            needed_fields: document_type.needed_fields(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// the puzzle rules extended by rules spanning multiple fields
+    pub fn cross_field(document_type: DocumentType) -> RuleSet {
+        RuleSet::new(document_type)
+            .with_rule(YearBefore(FieldType::BirthYear, FieldType::IssueYear))
+            .with_rule(YearBefore(FieldType::IssueYear, FieldType::ExpirationYear))
+            .with_rule(RequiredWhen {
+                field: FieldType::CountryID,
+                when: FieldType::EyeColor,
+                equals: "oth",
+            })
+    }
+
+    pub fn with_rule<R: RecordRule + 'static>(mut self, rule: R) -> RuleSet {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// human readable descriptions of every rule `passport` breaks
+    pub fn violations(&self, passport: &Passport) -> Vec<String> {
+        let missing = self
+            .needed_fields
+            .iter()
+            .filter(|key| !passport.fields.contains_key(key))
+            .map(|key| format!("{} is required", key));
+
+        let invalid = passport
+            .ordered_fields()
+            .filter(|field| !field.is_valid())
+            .map(|field| format!("{} is invalid: '{}'", field.field_type, field.field));
+
+        let broken = self
+            .rules
+            .iter()
+            .filter(|rule| !rule.is_satisfied(&passport.fields))
+            .map(|rule| rule.describe());
+
+        missing.chain(invalid).chain(broken).collect()
+    }
+
+    pub fn is_valid(&self, passport: &Passport) -> bool {
+        self.needed_fields
+            .iter()
+            .all(|key| passport.fields.contains_key(key))
+            && passport.fields.values().all(PassportField::is_valid)
+            && self
+                .rules
+                .iter()
+                .all(|rule| rule.is_satisfied(&passport.fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::passport;
+
+    #[test]
+    fn test_default_rules() {
+        let rules = RuleSet::default();
+        let p = passport("pid:087499704 hgt:74in ecl:oth iyr:2012 eyr:2030 byr:1980 hcl:#623a2f");
+        assert!(rules.is_valid(&p));
+        assert!(rules.violations(&p).is_empty());
+
+        let rules = RuleSet::new(DocumentType::Passport);
+        assert!(!rules.is_valid(&p));
+        assert_eq!(vec!["cid is required"], rules.violations(&p));
+    }
+
+    #[test]
+    fn test_cross_field_rules() {
+        let rules = RuleSet::cross_field(DocumentType::NorthPoleCredentials);
+
+        let p = passport("pid:087499704 hgt:74in ecl:oth iyr:2012 eyr:2030 byr:1980 hcl:#623a2f");
+        assert_eq!(
+            vec!["cid is required when ecl is oth"],
+            rules.violations(&p)
+        );
+
+        let p = passport("pid:087499704 hgt:74in ecl:grn iyr:2020 eyr:2020 byr:1980 hcl:#623a2f");
+        assert_eq!(vec!["iyr must be before eyr"], rules.violations(&p));
+
+        let p =
+            passport("pid:087499704 hgt:74in ecl:oth iyr:2012 eyr:2030 byr:1980 hcl:#623a2f cid:1");
+        assert!(rules.is_valid(&p));
+    }
+}
@@ -0,0 +1,13 @@
+use crate::passport::{Passport, PassportField};
+
+/// a passport of well-formed `key:value` fields separated by whitespace
+pub(crate) fn passport(input: &str) -> Passport {
+    let fields = input
+        .split_whitespace()
+        .map(|f| {
+            let split = f.splitn(2, ':').collect::<Vec<_>>();
+            PassportField::new(split[0], split[1])
+        })
+        .collect();
+    Passport::new(fields)
+}
@@ -0,0 +1,343 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::passport::{FieldType, Passport, NEEDED_FIELDS};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum HeightUnit {
+    Cm,
+    In,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Height {
+    pub value: u16,
+    pub unit: HeightUnit,
+}
+
+impl FromStr for Height {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // number + [in|cm] => len of at least 3
+        if s.len() < 3 || !s.is_char_boundary(s.len() - 2) {
+            return Err(());
+        }
+        let (number, suffix) = s.split_at(s.len() - 2);
+
+        let (unit, begin, end) = match suffix {
+            "cm" => (HeightUnit::Cm, 150, 193),
+            "in" => (HeightUnit::In, 59, 76),
+            _ => return Err(()),
+        };
+
+        let value = parse_number_in_range(number, begin, end).ok_or(())?;
+
+        Ok(Height { value, unit })
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.unit {
+            HeightUnit::Cm => "cm",
+            HeightUnit::In => "in",
+        };
+        write!(f, "{}{}", self.value, unit)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl FromStr for Rgb {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // '#' followed by exactly six lowercase hex digits
+        let hex = s.strip_prefix('#').ok_or(())?;
+        if hex.len() != 6 || !hex.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+            return Err(());
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| ());
+
+        Ok(Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        })
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum EyeColor {
+    Amber,
+    Blue,
+    Brown,
+    Gray,
+    Green,
+    Hazel,
+    Other,
+}
+
+impl FromStr for EyeColor {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let eye_color = match s {
+            "amb" => EyeColor::Amber,
+            "blu" => EyeColor::Blue,
+            "brn" => EyeColor::Brown,
+            "gry" => EyeColor::Gray,
+            "grn" => EyeColor::Green,
+            "hzl" => EyeColor::Hazel,
+            "oth" => EyeColor::Other,
+            _ => return Err(()),
+        };
+
+        Ok(eye_color)
+    }
+}
+
+impl fmt::Display for EyeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let eye_color = match self {
+            EyeColor::Amber => "amb",
+            EyeColor::Blue => "blu",
+            EyeColor::Brown => "brn",
+            EyeColor::Gray => "gry",
+            EyeColor::Green => "grn",
+            EyeColor::Hazel => "hzl",
+            EyeColor::Other => "oth",
+        };
+        write!(f, "{}", eye_color)
+    }
+}
+
+fn parse_number_in_range(number: &str, begin: u16, end: u16) -> Option<u16> {
+    // u16::from_str accepts a leading '+', the puzzle rules only allow digits
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let number = number.parse::<u16>().ok()?;
+
+    if number >= begin && number <= end {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+/// four digits; at least `begin` and at most `end`
+pub fn parse_year(year: &str, begin: u16, end: u16) -> Option<u16> {
+    if year.len() != 4 {
+        return None;
+    }
+
+    parse_number_in_range(year, begin, end)
+}
+
+/// a nine-digit number, including leading zeroes
+pub fn parse_passport_id(passport_id: &str) -> Option<[u8; 9]> {
+    let mut digits = [0; 9];
+    if passport_id.len() != digits.len() {
+        return None;
+    }
+
+    for (digit, c) in digits.iter_mut().zip(passport_id.chars()) {
+        *digit = c.to_digit(10)? as u8;
+    }
+
+    Some(digits)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum PassportError {
+    MissingField(FieldType),
+    InvalidField(FieldType, String),
+}
+
+impl fmt::Display for PassportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassportError::MissingField(field_type) => {
+                write!(f, "missing field {:?}", field_type)
+            }
+            PassportError::InvalidField(field_type, field) => {
+                write!(f, "invalid value '{}' for field {:?}", field, field_type)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ValidatedPassport {
+    pub birth_year: u16,
+    pub issue_year: u16,
+    pub expiration_year: u16,
+    pub height: Height,
+    pub hair_color: Rgb,
+    pub eye_color: EyeColor,
+    pub passport_id: [u8; 9],
+    /// `None` if missing or not a number, the rules ignore it
+    pub country_id: Option<u32>,
+}
+
+impl ValidatedPassport {
+    pub fn passport_id_string(&self) -> String {
+        self.passport_id
+            .iter()
+            .map(|digit| char::from(b'0' + digit))
+            .collect()
+    }
+}
+
+impl TryFrom<&Passport> for ValidatedPassport {
+    type Error = PassportError;
+
+    fn try_from(passport: &Passport) -> Result<Self, Self::Error> {
+        for key in NEEDED_FIELDS {
+            if !passport.fields.contains_key(key) {
+                return Err(PassportError::MissingField(*key));
+            }
+        }
+
+        fn field<T>(
+            passport: &Passport,
+            field_type: FieldType,
+            parse: impl Fn(&str) -> Option<T>,
+        ) -> Result<T, PassportError> {
+            let field = &passport.fields[&field_type].field;
+            parse(field).ok_or_else(|| PassportError::InvalidField(field_type, field.clone()))
+        }
+
+        Ok(ValidatedPassport {
+            birth_year: field(passport, FieldType::BirthYear, |f| {
+                parse_year(f, 1920, 2002)
+            })?,
+            issue_year: field(passport, FieldType::IssueYear, |f| {
+                parse_year(f, 2010, 2020)
+            })?,
+            expiration_year: field(passport, FieldType::ExpirationYear, |f| {
+                parse_year(f, 2020, 2030)
+            })?,
+            height: field(passport, FieldType::Height, |f| f.parse().ok())?,
+            hair_color: field(passport, FieldType::HairColor, |f| f.parse().ok())?,
+            eye_color: field(passport, FieldType::EyeColor, |f| f.parse().ok())?,
+            passport_id: field(passport, FieldType::PassportID, parse_passport_id)?,
+            country_id: passport
+                .fields
+                .get(&FieldType::CountryID)
+                .and_then(|f| f.field.parse().ok()),
+        })
+    }
+}
+
+impl TryFrom<Passport> for ValidatedPassport {
+    type Error = PassportError;
+
+    fn try_from(passport: Passport) -> Result<Self, Self::Error> {
+        ValidatedPassport::try_from(&passport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::passport::Valid;
+    use crate::test_util::passport;
+
+    #[test]
+    fn test_valid_passport() {
+        let p = passport(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f cid:129",
+        );
+        let p = ValidatedPassport::try_from(p).unwrap();
+
+        assert_eq!(1980, p.birth_year);
+        assert_eq!(
+            Height {
+                value: 74,
+                unit: HeightUnit::In
+            },
+            p.height
+        );
+        assert_eq!(
+            Rgb {
+                r: 0x62,
+                g: 0x3a,
+                b: 0x2f
+            },
+            p.hair_color
+        );
+        assert_eq!(EyeColor::Green, p.eye_color);
+        assert_eq!([0, 8, 7, 4, 9, 9, 7, 0, 4], p.passport_id);
+        assert_eq!("087499704", p.passport_id_string());
+        assert_eq!(Some(129), p.country_id);
+    }
+
+    #[test]
+    fn test_country_id_ignored() {
+        let p = passport(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f cid:abc",
+        );
+        assert!(p.is_valid());
+
+        let p = ValidatedPassport::try_from(p).unwrap();
+        assert_eq!(None, p.country_id);
+    }
+
+    #[test]
+    fn test_invalid_passport() {
+        let p = passport("pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980");
+        assert_eq!(
+            Err(PassportError::MissingField(FieldType::HairColor)),
+            ValidatedPassport::try_from(p)
+        );
+
+        let p = passport("pid:+87499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f");
+        assert_eq!(
+            Err(PassportError::InvalidField(
+                FieldType::PassportID,
+                String::from("+87499704")
+            )),
+            ValidatedPassport::try_from(p)
+        );
+    }
+
+    #[test]
+    fn test_field_parsing() {
+        assert_eq!(
+            Ok(Height {
+                value: 190,
+                unit: HeightUnit::Cm
+            }),
+            "190cm".parse()
+        );
+        assert_eq!(Err(()), "190in".parse::<Height>());
+        assert_eq!(Err(()), "190".parse::<Height>());
+
+        assert_eq!(Err(()), "#123abz".parse::<Rgb>());
+        assert_eq!(Err(()), "#123abcd".parse::<Rgb>());
+        assert_eq!(Err(()), "123abc".parse::<Rgb>());
+
+        assert_eq!(Err(()), "wat".parse::<EyeColor>());
+
+        assert_eq!(Some(2002), parse_year("2002", 1920, 2002));
+        assert_eq!(None, parse_year("2003", 1920, 2002));
+        assert_eq!(None, parse_year("+200", 0, 9999));
+    }
+}
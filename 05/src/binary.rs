@@ -0,0 +1,181 @@
+use crate::seat::{DecodeError, PlaneLayout, SeatIndex};
+
+// a boarding pass is a binary number: F and L select the lower half (0),
+// B and R the upper half (1)
+const ROW_LETTERS: (char, char) = ('F', 'B');
+const COLUMN_LETTERS: (char, char) = ('L', 'R');
+
+fn letters(layout: &PlaneLayout, position: usize) -> (char, char) {
+    if position < layout.row_bits() as usize {
+        ROW_LETTERS
+    } else {
+        COLUMN_LETTERS
+    }
+}
+
+/// Decodes a boarding pass straight into its seat id.
+///
+/// Yields the same ids as `calc_seat_index` followed by `calc_seat_id`
+/// without partitioning any ranges.
+pub fn decode_seat_id(layout: &PlaneLayout, boarding_pass: &str) -> Result<u32, DecodeError> {
+    let len = boarding_pass.chars().count();
+    if len != layout.pass_len() {
+        return Err(DecodeError::WrongLength {
+            expected: layout.pass_len(),
+            actual: len,
+        });
+    }
+
+    let mut seat_id = 0;
+    for (position, c) in boarding_pass.chars().enumerate() {
+        let (zero, one) = letters(layout, position);
+        let bit = match c {
+            c if c == zero => 0,
+            c if c == one => 1,
+            c => return Err(DecodeError::InvalidChar { position, c }),
+        };
+
+        seat_id = (seat_id << 1) | bit;
+    }
+
+    Ok(seat_id)
+}
+
+pub fn decode_seat_index(
+    layout: &PlaneLayout,
+    boarding_pass: &str,
+) -> Result<SeatIndex, DecodeError> {
+    decode_seat_id(layout, boarding_pass).map(|seat_id| layout.seat_index(seat_id))
+}
+
+/// Encodes a seat id into its boarding pass, `None` if the plane has no such seat.
+pub fn encode_seat_id(layout: &PlaneLayout, seat_id: u32) -> Option<String> {
+    let len = layout.pass_len();
+    if len >= u32::BITS as usize || seat_id >> len != 0 {
+        return None;
+    }
+
+    let boarding_pass = (0..len)
+        .map(|position| {
+            let (zero, one) = letters(layout, position);
+            if seat_id >> (len - 1 - position) & 1 == 0 {
+                zero
+            } else {
+                one
+            }
+        })
+        .collect();
+
+    Some(boarding_pass)
+}
+
+pub fn encode_seat_index(layout: &PlaneLayout, seat_index: &SeatIndex) -> Option<String> {
+    if !layout.contains(seat_index) {
+        return None;
+    }
+
+    encode_seat_id(layout, layout.seat_id(seat_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seat::{calc_seat_id, calc_seat_index};
+    use proptest::prelude::*;
+
+    fn layouts() -> impl Strategy<Value = PlaneLayout> {
+        (0..16u32, 0..16u32)
+            .prop_map(|(row_bits, col_bits)| PlaneLayout::new(row_bits, col_bits).unwrap())
+    }
+
+    fn boarding_passes() -> impl Strategy<Value = (PlaneLayout, String)> {
+        layouts().prop_flat_map(|layout| {
+            let letters = (0..layout.pass_len())
+                .map(|position| {
+                    let (zero, one) = letters(&layout, position);
+                    prop_oneof![Just(zero), Just(one)]
+                })
+                .collect::<Vec<_>>();
+
+            (Just(layout), letters.prop_map(|l| l.into_iter().collect()))
+        })
+    }
+
+    #[test]
+    fn test_decode_examples() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(Ok(357), decode_seat_id(&layout, "FBFBBFFRLR"));
+        assert_eq!(Ok(567), decode_seat_id(&layout, "BFFFBBFRRR"));
+        assert_eq!(Ok(119), decode_seat_id(&layout, "FFFBBBFRRR"));
+        assert_eq!(
+            Ok(SeatIndex(102, 4)),
+            decode_seat_index(&layout, "BBFFBBFRLL")
+        );
+
+        assert_eq!(
+            Err(DecodeError::InvalidChar {
+                position: 7,
+                c: 'B'
+            }),
+            decode_seat_id(&layout, "FBFBBFFBLR")
+        );
+        assert_eq!(
+            Err(DecodeError::WrongLength {
+                expected: 10,
+                actual: 11
+            }),
+            decode_seat_id(&layout, "FBFBBFFRLRX")
+        );
+    }
+
+    #[test]
+    fn test_encode_examples() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(
+            Some(String::from("FBFBBFFRLR")),
+            encode_seat_id(&layout, 357)
+        );
+        assert_eq!(
+            Some(String::from("BBFFBBFRLL")),
+            encode_seat_index(&layout, &SeatIndex(102, 4))
+        );
+        assert_eq!(None, encode_seat_id(&layout, 1024));
+        assert_eq!(None, encode_seat_index(&layout, &SeatIndex(5, 8)));
+    }
+
+    proptest! {
+        #[test]
+        fn decode_agrees_with_calc((layout, boarding_pass) in boarding_passes()) {
+            let chars = boarding_pass.chars().collect::<Vec<_>>();
+            let seat_index = calc_seat_index(&layout, &chars).unwrap();
+
+            prop_assert_eq!(
+                Ok(calc_seat_id(&layout, seat_index)),
+                decode_seat_id(&layout, &boarding_pass)
+            );
+            prop_assert_eq!(Ok(seat_index), decode_seat_index(&layout, &boarding_pass));
+        }
+
+        #[test]
+        fn encode_inverts_calc((layout, boarding_pass) in boarding_passes()) {
+            let chars = boarding_pass.chars().collect::<Vec<_>>();
+            let seat_index = calc_seat_index(&layout, &chars).unwrap();
+
+            prop_assert_eq!(Some(boarding_pass.clone()), encode_seat_index(&layout, &seat_index));
+            prop_assert_eq!(
+                Some(boarding_pass),
+                encode_seat_id(&layout, calc_seat_id(&layout, seat_index))
+            );
+        }
+
+        #[test]
+        fn decode_inverts_encode(layout in layouts(), seat_id: u32) {
+            let seat_id = seat_id % (layout.rows() * layout.columns());
+            let boarding_pass = encode_seat_id(&layout, seat_id).unwrap();
+
+            prop_assert_eq!(Ok(seat_id), decode_seat_id(&layout, &boarding_pass));
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::seat::PlaneLayout;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SeatError {
+    NoFreeSeat,
+    Ambiguous(Vec<u32>),
+}
+
+impl fmt::Display for SeatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeatError::NoFreeSeat => write!(f, "no free seat matches"),
+            SeatError::Ambiguous(candidates) => {
+                write!(f, "{} seats match: {:?}", candidates.len(), candidates)
+            }
+        }
+    }
+}
+
+/// every free seat id between the lowest and the highest taken seat id
+pub fn find_free_seats(ids: &[u32]) -> Vec<u32> {
+    let taken = ids.iter().collect::<HashSet<_>>();
+
+    match (ids.iter().min(), ids.iter().max()) {
+        (Some(&min), Some(&max)) => (min..=max).filter(|id| !taken.contains(id)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Free seats matching the puzzle: the seats with ids +1 and -1 are taken and
+/// the seat isn't part of the missing rows at the very front or back, i.e. its
+/// row is neither before the first nor after the last row with a taken seat.
+pub fn find_candidate_seats(layout: &PlaneLayout, ids: &[u32]) -> Vec<u32> {
+    let taken = ids.iter().collect::<HashSet<_>>();
+    let rows = ids.iter().map(|id| layout.seat_index(*id).0);
+    let (first_row, last_row) = match (rows.clone().min(), rows.max()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Vec::new(),
+    };
+
+    find_free_seats(ids)
+        .into_iter()
+        .filter(|id| taken.contains(&(id - 1)) && taken.contains(&(id + 1)))
+        .filter(|id| (first_row..=last_row).contains(&layout.seat_index(*id).0))
+        .collect()
+}
+
+/// the single seat left by `find_candidate_seats`
+pub fn find_own_seat(layout: &PlaneLayout, ids: &[u32]) -> Result<u32, SeatError> {
+    let candidates = find_candidate_seats(layout, ids);
+
+    match candidates.len() {
+        0 => Err(SeatError::NoFreeSeat),
+        1 => Ok(candidates[0]),
+        _ => Err(SeatError::Ambiguous(candidates)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_free_seats() {
+        assert!(find_free_seats(&[]).is_empty());
+        assert!(find_free_seats(&[5]).is_empty());
+        assert_eq!(vec![6, 8, 9], find_free_seats(&[10, 5, 7, 7]));
+    }
+
+    #[test]
+    fn test_find_own_seat() {
+        let layout = PlaneLayout::default();
+
+        // row 1 is the first existing row, 9 and 10 don't exist
+        let ids = (11..20).filter(|id| *id != 14).collect::<Vec<_>>();
+        assert_eq!(Ok(14), find_own_seat(&layout, &ids));
+
+        assert_eq!(Err(SeatError::NoFreeSeat), find_own_seat(&layout, &[]));
+        assert_eq!(
+            Err(SeatError::NoFreeSeat),
+            find_own_seat(&layout, &[11, 14])
+        );
+        assert_eq!(
+            Err(SeatError::Ambiguous(vec![12, 14])),
+            find_own_seat(&layout, &[11, 13, 15])
+        );
+
+        // one seat per row: an empty row between two taken ones isn't missing
+        let layout = PlaneLayout::new(4, 0).unwrap();
+        assert_eq!(Ok(4), find_own_seat(&layout, &[3, 5]));
+    }
+}
@@ -1,9 +1,11 @@
-use std::{
-    collections::HashSet,
-    io::{self, BufRead},
-};
+use std::io::{self, BufRead};
 use std::{fmt::Debug, fs::File, path::Path, str::FromStr};
 
+pub mod binary;
+pub mod free_seats;
+pub mod seat;
+pub mod seat_map;
+
 fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where
     P: AsRef<Path>,
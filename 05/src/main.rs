@@ -1,113 +1,54 @@
-use std::io::{self, BufRead};
-use std::path::Path;
-use std::str::FromStr;
-use std::{fmt::Debug, fs::File};
+use std::{env, process};
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+use day_5::binary::decode_seat_id;
+use day_5::free_seats::find_own_seat;
+use day_5::seat::PlaneLayout;
+use day_5::seat_map::SeatMap;
 
-fn get_input<T>(file: &str) -> Vec<T>
-where
-    T: FromStr,
-    <T as FromStr>::Err: Debug,
-{
+fn print_seat_map(layout: &PlaneLayout, input: &[String], own_seat: Option<u32>) {
     // unwrap is acceptable because of known input
-    read_lines(file)
-        .unwrap()
-        .map(|line| line.unwrap().parse::<T>().unwrap())
-        .collect::<Vec<_>>()
-}
-
-#[derive(Debug)]
-struct SeatIndex(u32, u32);
-
-#[derive(Debug)]
-struct Range(u32, u32);
-
-enum TakeType {
-    Upper,
-    Lower,
-}
-
-impl From<&char> for TakeType {
-    fn from(c: &char) -> Self {
-        match c {
-            'F' | 'L' => TakeType::Lower,
-            'B' | 'R' => TakeType::Upper,
-            _ => panic!()
-        }
+    let mut seat_map = SeatMap::from_boarding_passes(*layout, input).unwrap();
+    if let Some(own_seat) = own_seat {
+        seat_map = seat_map.with_own_seat(layout.seat_index(own_seat));
     }
-}
-
-fn perform_binary_partition(range: &Range, partition_take: &TakeType) -> Range {
-    let middle = ((range.1 - range.0) / 2) + range.0;
 
-    match partition_take {
-        TakeType::Upper => Range(middle + 1, range.1),
-        TakeType::Lower => Range(range.0, middle),
+    println!("{}", seat_map);
+    println!("ROWS: {:?}", seat_map.row_occupancy());
+    println!("COLUMNS: {:?}", seat_map.column_occupancy());
+
+    let mut duplicates = seat_map.duplicates().into_iter().collect::<Vec<_>>();
+    duplicates.sort_unstable_by_key(|(seat, _)| layout.seat_id(seat));
+    for (seat, passes) in duplicates {
+        println!(
+            "DUPLICATE: seat {} (row {}, column {}) has {} boarding passes",
+            layout.seat_id(&seat),
+            seat.0,
+            seat.1,
+            passes
+        );
     }
 }
 
-fn calc(definition: &[char], init_range: Range) -> u32 {
-    let mut r: Range = init_range;
+fn main() {
+    let input: Vec<String> = day_5::get_input("./input");
+    let layout = PlaneLayout::default();
 
-    let definition = definition
+    let ids = input
         .iter()
-        .map(|c| TakeType::from(c))
+        // unwrap is acceptable because of known input
+        .map(|boarding_pass| decode_seat_id(&layout, boarding_pass).unwrap())
         .collect::<Vec<_>>();
+    let own_seat = find_own_seat(&layout, &ids);
 
-    for take_type in &definition {
-        r = perform_binary_partition(&r, take_type);
-    }
-
-    r.0
-}
-
-fn calc_row(row_definition: &[char]) -> u32 {
-    if row_definition.len() != 7 {
-        panic!()
+    if env::args().nth(1).as_deref() == Some("map") {
+        print_seat_map(&layout, &input, own_seat.clone().ok());
     }
 
-    calc(&row_definition, Range(0, 127))
-}
-
-fn calc_column(column_definition: &[char]) -> u32 {
-    if column_definition.len() != 3 {
-        panic!()
-    }
-
-    calc(&column_definition, Range(0, 7))
-}
-
-fn calc_seat_index(boarding_pass: &Vec<char>) -> SeatIndex {
-    let row_definition = &boarding_pass[0..7];
-    let column_definition = &boarding_pass[7..];
-
-    return SeatIndex(calc_row(row_definition), calc_column(column_definition));
-}
-
-fn calc_seat_id(seat_index: SeatIndex) -> u32 {
-    seat_index.0 * 8 + seat_index.1
-}
-
-fn main() {
-    let input: Vec<String> = get_input("./input");
-    let input = input
-        .iter()
-        .map(|boarding_pass| boarding_pass.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    let mut result = input.iter().map(calc_seat_index).map(calc_seat_id).collect::<Vec<_>>();
-    result.sort();
-
-    for i in 0..result.len() - 1 {
-        if result[i] + 1 != result[i + 1] {
-            println!("{}, ", result[i] + 1);
+    match own_seat {
+        Ok(seat_id) => println!("RESULT: {}", seat_id),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
         }
     }
 }
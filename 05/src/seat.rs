@@ -0,0 +1,295 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct SeatIndex(pub u32, pub u32);
+
+#[derive(Debug)]
+struct Range(u32, u32);
+
+/// The number of rows and columns as powers of two.
+///
+/// Seat ids have to fit into a u32, which `PlaneLayout::new` checks.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct PlaneLayout {
+    row_bits: u32,
+    col_bits: u32,
+}
+
+impl Default for PlaneLayout {
+    /// 128 rows with 8 columns each
+    fn default() -> Self {
+        PlaneLayout {
+            row_bits: 7,
+            col_bits: 3,
+        }
+    }
+}
+
+impl PlaneLayout {
+    pub fn new(row_bits: u32, col_bits: u32) -> Result<PlaneLayout, DecodeError> {
+        match row_bits.checked_add(col_bits) {
+            Some(bits) if bits < u32::BITS => Ok(PlaneLayout { row_bits, col_bits }),
+            _ => Err(DecodeError::LayoutTooLarge { row_bits, col_bits }),
+        }
+    }
+
+    pub fn row_bits(&self) -> u32 {
+        self.row_bits
+    }
+
+    pub fn col_bits(&self) -> u32 {
+        self.col_bits
+    }
+
+    pub fn rows(&self) -> u32 {
+        1 << self.row_bits
+    }
+
+    pub fn columns(&self) -> u32 {
+        1 << self.col_bits
+    }
+
+    pub fn pass_len(&self) -> usize {
+        (self.row_bits + self.col_bits) as usize
+    }
+
+    pub fn seat_id(&self, seat_index: &SeatIndex) -> u32 {
+        seat_index.0 * self.columns() + seat_index.1
+    }
+
+    pub fn seat_index(&self, seat_id: u32) -> SeatIndex {
+        SeatIndex(seat_id >> self.col_bits, seat_id & (self.columns() - 1))
+    }
+
+    /// whether the plane has a seat at `seat_index`
+    pub fn contains(&self, seat_index: &SeatIndex) -> bool {
+        seat_index.0 < self.rows() && seat_index.1 < self.columns()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DecodeError {
+    WrongLength {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidChar {
+        position: usize,
+        c: char,
+    },
+    LayoutTooLarge {
+        row_bits: u32,
+        col_bits: u32,
+    },
+    /// a seat outside of the rows and columns of the layout
+    NoSuchSeat(SeatIndex),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::WrongLength { expected, actual } => write!(
+                f,
+                "boarding pass has {} characters, expected {}",
+                actual, expected
+            ),
+            DecodeError::InvalidChar { position, c } => {
+                write!(f, "unexpected '{}' at position {}", c, position)
+            }
+            DecodeError::LayoutTooLarge { row_bits, col_bits } => write!(
+                f,
+                "{} row bits and {} column bits don't fit into a seat id",
+                row_bits, col_bits
+            ),
+            DecodeError::NoSuchSeat(seat_index) => write!(
+                f,
+                "the plane has no seat in row {} and column {}",
+                seat_index.0, seat_index.1
+            ),
+        }
+    }
+}
+
+enum TakeType {
+    Upper,
+    Lower,
+}
+
+/// the letters of the boarding pass selecting the lower or upper half
+struct Partition {
+    lower: char,
+    upper: char,
+}
+
+const ROW_PARTITION: Partition = Partition {
+    lower: 'F',
+    upper: 'B',
+};
+const COLUMN_PARTITION: Partition = Partition {
+    lower: 'L',
+    upper: 'R',
+};
+
+impl TryFrom<(&Partition, char)> for TakeType {
+    type Error = char;
+
+    fn try_from((partition, c): (&Partition, char)) -> Result<Self, Self::Error> {
+        match c {
+            c if c == partition.lower => Ok(TakeType::Lower),
+            c if c == partition.upper => Ok(TakeType::Upper),
+            c => Err(c),
+        }
+    }
+}
+
+fn perform_binary_partition(range: &Range, partition_take: &TakeType) -> Range {
+    let middle = ((range.1 - range.0) / 2) + range.0;
+
+    match partition_take {
+        TakeType::Upper => Range(middle + 1, range.1),
+        TakeType::Lower => Range(range.0, middle),
+    }
+}
+
+fn calc(
+    definition: &[char],
+    offset: usize,
+    partition: &Partition,
+    init_range: Range,
+) -> Result<u32, DecodeError> {
+    let mut r: Range = init_range;
+
+    let definition = definition
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            TakeType::try_from((partition, *c)).map_err(|c| DecodeError::InvalidChar {
+                position: offset + i,
+                c,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for take_type in &definition {
+        r = perform_binary_partition(&r, take_type);
+    }
+
+    Ok(r.0)
+}
+
+fn calc_row(layout: &PlaneLayout, row_definition: &[char]) -> Result<u32, DecodeError> {
+    calc(
+        row_definition,
+        0,
+        &ROW_PARTITION,
+        Range(0, layout.rows() - 1),
+    )
+}
+
+fn calc_column(layout: &PlaneLayout, column_definition: &[char]) -> Result<u32, DecodeError> {
+    calc(
+        column_definition,
+        layout.row_bits as usize,
+        &COLUMN_PARTITION,
+        Range(0, layout.columns() - 1),
+    )
+}
+
+pub fn calc_seat_index(
+    layout: &PlaneLayout,
+    boarding_pass: &[char],
+) -> Result<SeatIndex, DecodeError> {
+    if boarding_pass.len() != layout.pass_len() {
+        return Err(DecodeError::WrongLength {
+            expected: layout.pass_len(),
+            actual: boarding_pass.len(),
+        });
+    }
+
+    let (row_definition, column_definition) = boarding_pass.split_at(layout.row_bits as usize);
+
+    Ok(SeatIndex(
+        calc_row(layout, row_definition)?,
+        calc_column(layout, column_definition)?,
+    ))
+}
+
+pub fn calc_seat_id(layout: &PlaneLayout, seat_index: SeatIndex) -> u32 {
+    layout.seat_id(&seat_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat_index(layout: &PlaneLayout, boarding_pass: &str) -> Result<SeatIndex, DecodeError> {
+        calc_seat_index(layout, &boarding_pass.chars().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_default_layout() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(Ok(SeatIndex(44, 5)), seat_index(&layout, "FBFBBFFRLR"));
+        assert_eq!(Ok(SeatIndex(70, 7)), seat_index(&layout, "BFFFBBFRRR"));
+        assert_eq!(Ok(SeatIndex(14, 7)), seat_index(&layout, "FFFBBBFRRR"));
+        assert_eq!(Ok(SeatIndex(102, 4)), seat_index(&layout, "BBFFBBFRLL"));
+
+        assert_eq!(820, calc_seat_id(&layout, SeatIndex(102, 4)));
+    }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout = PlaneLayout::new(2, 4).unwrap();
+
+        assert_eq!(Ok(SeatIndex(2, 9)), seat_index(&layout, "BFRLLR"));
+        assert_eq!(41, calc_seat_id(&layout, SeatIndex(2, 9)));
+        assert_eq!(Ok(SeatIndex(0, 0)), seat_index(&layout, "FFLLLL"));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let layout = PlaneLayout::default();
+
+        assert_eq!(
+            Err(DecodeError::WrongLength {
+                expected: 10,
+                actual: 9
+            }),
+            seat_index(&layout, "FBFBBFFRL")
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidChar {
+                position: 3,
+                c: 'X'
+            }),
+            seat_index(&layout, "FBFXBFFRLR")
+        );
+        // columns are only described by L and R
+        assert_eq!(
+            Err(DecodeError::InvalidChar {
+                position: 8,
+                c: 'F'
+            }),
+            seat_index(&layout, "FBFBBFFRFR")
+        );
+    }
+
+    #[test]
+    fn test_layout_bounds() {
+        assert_eq!(
+            Err(DecodeError::LayoutTooLarge {
+                row_bits: 30,
+                col_bits: 2
+            }),
+            PlaneLayout::new(30, 2)
+        );
+        assert!(PlaneLayout::new(32, 0).is_err());
+        assert!(PlaneLayout::new(u32::MAX, 1).is_err());
+
+        let layout = PlaneLayout::new(31, 0).unwrap();
+        assert_eq!(1 << 31, layout.rows());
+        assert_eq!(1, layout.columns());
+    }
+}
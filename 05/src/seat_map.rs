@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::seat::{calc_seat_index, DecodeError, PlaneLayout, SeatIndex};
+
+const TAKEN: char = '#';
+const FREE: char = '.';
+const OWN: char = 'O';
+
+/// Occupancy of every seat of a plane, built from all boarding passes.
+pub struct SeatMap {
+    layout: PlaneLayout,
+    /// number of boarding passes per seat, indexed by seat id
+    passes: Vec<usize>,
+    own_seat: Option<SeatIndex>,
+}
+
+impl SeatMap {
+    pub fn new(layout: PlaneLayout, seats: &[SeatIndex]) -> Result<SeatMap, DecodeError> {
+        let mut passes = vec![0; (layout.rows() * layout.columns()) as usize];
+        for seat in seats {
+            if !layout.contains(seat) {
+                return Err(DecodeError::NoSuchSeat(*seat));
+            }
+            passes[layout.seat_id(seat) as usize] += 1;
+        }
+
+        Ok(SeatMap {
+            layout,
+            passes,
+            own_seat: None,
+        })
+    }
+
+    pub fn from_boarding_passes(
+        layout: PlaneLayout,
+        boarding_passes: &[String],
+    ) -> Result<SeatMap, DecodeError> {
+        let seats = boarding_passes
+            .iter()
+            .map(|pass| calc_seat_index(&layout, &pass.chars().collect::<Vec<_>>()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        SeatMap::new(layout, &seats)
+    }
+
+    pub fn with_own_seat(mut self, own_seat: SeatIndex) -> SeatMap {
+        self.own_seat = Some(own_seat);
+        self
+    }
+
+    fn passes_of(&self, seat: &SeatIndex) -> usize {
+        self.passes[self.layout.seat_id(seat) as usize]
+    }
+
+    pub fn is_taken(&self, seat: &SeatIndex) -> bool {
+        self.passes_of(seat) > 0
+    }
+
+    fn seats(&self) -> impl Iterator<Item = SeatIndex> + '_ {
+        (0..self.layout.rows())
+            .flat_map(move |row| (0..self.layout.columns()).map(move |col| SeatIndex(row, col)))
+    }
+
+    /// taken seats per row, front to back
+    pub fn row_occupancy(&self) -> Vec<u32> {
+        let mut occupancy = vec![0; self.layout.rows() as usize];
+        for seat in self.seats().filter(|seat| self.is_taken(seat)) {
+            occupancy[seat.0 as usize] += 1;
+        }
+
+        occupancy
+    }
+
+    /// taken seats per column, left to right
+    pub fn column_occupancy(&self) -> Vec<u32> {
+        let mut occupancy = vec![0; self.layout.columns() as usize];
+        for seat in self.seats().filter(|seat| self.is_taken(seat)) {
+            occupancy[seat.1 as usize] += 1;
+        }
+
+        occupancy
+    }
+
+    /// seats with more than one boarding pass and how many passes they have
+    pub fn duplicates(&self) -> HashMap<SeatIndex, usize> {
+        self.seats()
+            .map(|seat| (seat, self.passes_of(&seat)))
+            .filter(|(_, passes)| *passes > 1)
+            .collect()
+    }
+}
+
+impl fmt::Display for SeatMap {
+    /// one line per row, `#` taken, `.` free and `O` the own seat
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row_width = (self.layout.rows() - 1).to_string().len();
+
+        for row in 0..self.layout.rows() {
+            let seats = (0..self.layout.columns())
+                .map(|col| SeatIndex(row, col))
+                .map(|seat| match seat {
+                    seat if Some(seat) == self.own_seat => OWN,
+                    seat if self.is_taken(&seat) => TAKEN,
+                    _ => FREE,
+                })
+                .collect::<String>();
+
+            writeln!(f, "{:>width$} {}", row, seats, width = row_width)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat_map() -> SeatMap {
+        let layout = PlaneLayout::new(2, 2).unwrap();
+        let boarding_passes = ["FBLR", "FBRR", "FBLR", "BBLL", "FFRL"]
+            .iter()
+            .map(|pass| String::from(*pass))
+            .collect::<Vec<_>>();
+
+        SeatMap::from_boarding_passes(layout, &boarding_passes).unwrap()
+    }
+
+    #[test]
+    fn test_display() {
+        let seat_map = seat_map().with_own_seat(SeatIndex(1, 0));
+
+        assert_eq!("0 ..#.\n1 O#.#\n2 ....\n3 #...\n", seat_map.to_string());
+    }
+
+    #[test]
+    fn test_statistics() {
+        let seat_map = seat_map();
+
+        assert_eq!(vec![1, 2, 0, 1], seat_map.row_occupancy());
+        assert_eq!(vec![1, 1, 1, 1], seat_map.column_occupancy());
+
+        let duplicates = seat_map.duplicates();
+        assert_eq!(1, duplicates.len());
+        assert_eq!(Some(&2), duplicates.get(&SeatIndex(1, 1)));
+    }
+
+    #[test]
+    fn test_seats_out_of_bounds() {
+        let layout = PlaneLayout::new(0, 1).unwrap();
+
+        assert!(SeatMap::new(layout, &[SeatIndex(0, 1)]).is_ok());
+        assert!(matches!(
+            SeatMap::new(layout, &[SeatIndex(0, 1), SeatIndex(0, 3)]),
+            Err(DecodeError::NoSuchSeat(SeatIndex(0, 3)))
+        ));
+        assert!(matches!(
+            SeatMap::new(layout, &[SeatIndex(1, 0)]),
+            Err(DecodeError::NoSuchSeat(SeatIndex(1, 0)))
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+pub const QUESTIONS: std::ops::RangeInclusive<char> = 'a'..='z';
+
+/// The questions one person answered with "yes", one bit per question `a..=z`.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Default)]
+pub struct AnswerSet(u32);
+
+fn bit(question: char) -> Option<u32> {
+    if QUESTIONS.contains(&question) {
+        Some(1 << (question as u32 - 'a' as u32))
+    } else {
+        None
+    }
+}
+
+impl AnswerSet {
+    pub fn new() -> AnswerSet {
+        AnswerSet(0)
+    }
+
+    /// all questions `a..=z`
+    pub fn all() -> AnswerSet {
+        QUESTIONS.collect()
+    }
+
+    /// Adds `question`, returns `false` if it already was part of the set or
+    /// isn't one of `a..=z`.
+    pub fn insert(&mut self, question: char) -> bool {
+        match bit(question) {
+            Some(bit) if self.0 & bit == 0 => {
+                self.0 |= bit;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn contains(&self, question: char) -> bool {
+        bit(question).is_some_and(|bit| self.0 & bit != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(&self, other: &AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: &AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 & other.0)
+    }
+
+    pub fn symmetric_difference(&self, other: &AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 ^ other.0)
+    }
+
+    /// the answered questions in alphabetical order
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        QUESTIONS.filter(move |question| self.contains(*question))
+    }
+}
+
+impl FromIterator<char> for AnswerSet {
+    /// questions outside of `a..=z` are skipped
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut answers = AnswerSet::new();
+        for question in iter {
+            answers.insert(question);
+        }
+
+        answers
+    }
+}
+
+impl fmt::Display for AnswerSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.iter().collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_set() {
+        let mut answers = AnswerSet::new();
+        assert!(answers.is_empty());
+        assert!(answers.insert('c'));
+        assert!(answers.insert('a'));
+        assert!(!answers.insert('a'));
+        assert!(!answers.insert('A'));
+        assert!(!answers.insert('1'));
+
+        assert_eq!(2, answers.len());
+        assert!(answers.contains('a'));
+        assert!(!answers.contains('b'));
+        assert_eq!("ac", answers.to_string());
+        assert_eq!(26, AnswerSet::all().len());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let abc = "abc".chars().collect::<AnswerSet>();
+        let bcd = "bcd".chars().collect::<AnswerSet>();
+
+        assert_eq!("abcd", abc.union(&bcd).to_string());
+        assert_eq!("bc", abc.intersection(&bcd).to_string());
+        assert_eq!("ad", abc.symmetric_difference(&bcd).to_string());
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+
+use crate::answer_set::{AnswerSet, QUESTIONS};
+use records::ReadRecords;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupAnswer {
+    persons: Vec<AnswerSet>,
+}
+
+impl GroupAnswer {
+    pub fn new(persons: Vec<AnswerSet>) -> GroupAnswer {
+        GroupAnswer { persons }
+    }
+
+    pub fn nr_of_persons(&self) -> usize {
+        self.persons.len()
+    }
+
+    pub fn persons(&self) -> &[AnswerSet] {
+        &self.persons
+    }
+
+    /// questions anyone in the group answered
+    pub fn union(&self) -> AnswerSet {
+        self.persons
+            .iter()
+            .fold(AnswerSet::new(), |acc, person| acc.union(person))
+    }
+
+    /// questions everyone in the group answered
+    pub fn intersection(&self) -> AnswerSet {
+        if self.persons.is_empty() {
+            return AnswerSet::new();
+        }
+
+        self.persons
+            .iter()
+            .fold(AnswerSet::all(), |acc, person| acc.intersection(person))
+    }
+
+    /// questions answered by an odd number of persons
+    pub fn symmetric_difference(&self) -> AnswerSet {
+        self.persons.iter().fold(AnswerSet::new(), |acc, person| {
+            acc.symmetric_difference(person)
+        })
+    }
+
+    /// questions answered by `k` or more persons
+    pub fn answered_by_at_least(&self, k: usize) -> AnswerSet {
+        let histogram = self.histogram();
+
+        QUESTIONS
+            .filter(|question| histogram.get(question).copied().unwrap_or(0) >= k)
+            .collect()
+    }
+
+    /// number of persons per answered question
+    pub fn histogram(&self) -> BTreeMap<char, usize> {
+        let mut histogram = BTreeMap::new();
+        for question in self.persons.iter().flat_map(AnswerSet::iter) {
+            *histogram.entry(question).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    pub fn get_anyone_answers(&self) -> usize {
+        self.union().len()
+    }
+
+    pub fn get_mutual_answers(&self) -> usize {
+        self.intersection().len()
+    }
+}
+
+fn parse_group(record: Vec<String>) -> GroupAnswer {
+    let persons = record.iter().map(|line| line.chars().collect()).collect();
+
+    GroupAnswer::new(persons)
+}
+
+/// lazily parses one group per blank-line-separated record of `input`
+pub fn parse_input<R: BufRead>(input: R) -> impl Iterator<Item = io::Result<GroupAnswer>> {
+    input.records().map(|record| record.map(parse_group))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(persons: &[&str]) -> GroupAnswer {
+        GroupAnswer::new(persons.iter().map(|p| p.chars().collect()).collect())
+    }
+
+    #[test]
+    fn test_puzzle_parts() {
+        let input = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
+        let groups = parse_input(input.as_bytes())
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+
+        let anyone = groups.iter().map(GroupAnswer::get_anyone_answers);
+        assert_eq!(11, anyone.sum::<usize>());
+
+        let everyone = groups.iter().map(GroupAnswer::get_mutual_answers);
+        assert_eq!(6, everyone.sum::<usize>());
+    }
+
+    #[test]
+    fn test_group_operations() {
+        let g = group(&["abc", "bcd", "cx"]);
+
+        assert_eq!(3, g.nr_of_persons());
+        assert_eq!("abcdx", g.union().to_string());
+        assert_eq!("c", g.intersection().to_string());
+        assert_eq!("acdx", g.symmetric_difference().to_string());
+        assert_eq!("bc", g.answered_by_at_least(2).to_string());
+        assert_eq!(
+            vec![('a', 1), ('b', 2), ('c', 3), ('d', 1), ('x', 1)],
+            g.histogram().into_iter().collect::<Vec<_>>()
+        );
+
+        let empty = group(&[]);
+        assert!(empty.intersection().is_empty());
+        assert_eq!(0, empty.get_anyone_answers());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod answer_set;
+pub mod group_answer;
+pub mod report;
+pub mod validation;
@@ -1,91 +1,46 @@
-use std::{collections::HashMap, iter::FromIterator};
-use std::path::Path;
-use std::str::FromStr;
-use std::{
-    collections::HashSet,
-    io::{self, BufRead},
-};
-use std::{fmt::Debug, fs::File};
-
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-fn get_input<T>(file: &str) -> Vec<T>
-where
-    T: FromStr,
-    <T as FromStr>::Err: Debug,
-{
-    // unwrap is acceptable because of known input
-    read_lines(file)
-        .unwrap()
-        .map(|line| line.unwrap().parse::<T>().unwrap())
-        .collect::<Vec<_>>()
-}
-
-#[derive(Debug)]
-struct GroupAnswer {
-    nr_of_persons: usize,
-    answers: HashMap<char, usize>,
-}
-
-impl GroupAnswer {
-    fn new(all_answers: Vec<char>, nr_of_persons: usize) -> GroupAnswer {
-        let mut answers = HashMap::new();
-        for c in all_answers {
-            if !answers.contains_key(&c) {
-                answers.insert(c, 0);
-            }
-
-            *answers.get_mut(&c).unwrap() += 1;
-        }
-
-        GroupAnswer {
-            nr_of_persons,
-            answers
+use std::fs::File;
+use std::io::BufReader;
+use std::{env, process};
+
+use day06::group_answer::GroupAnswer;
+use day06::report::SurveyReport;
+use day06::validation::{Strictness, ValidatingParser};
+
+/// usage: day06 report [table|json]
+fn print_report(groups: &[GroupAnswer], format: Option<&str>) {
+    let report = SurveyReport::new(groups);
+
+    match format {
+        None | Some("table") => print!("{}", report),
+        // unwrap is acceptable, the report only holds numbers and chars
+        Some("json") => println!("{}", report.to_json().unwrap()),
+        Some(format) => {
+            eprintln!("unknown format '{}', expected table or json", format);
+            process::exit(1);
         }
     }
-
-    fn get_mutual_answers(&self) -> usize {
-        self.answers.values().filter(|a| **a == self.nr_of_persons).count()
-    }
-}
-
-fn parse_input(input: Vec<String>) -> Vec<GroupAnswer> {
-    let mut group_answers: Vec<GroupAnswer> = Vec::new();
-    let mut curr_answers: Vec<char> = Vec::new();
-    let mut curr_nr_of_persons: usize = 0;
-
-    for line in input {
-        if line.is_empty() {
-            group_answers.push(GroupAnswer::new(curr_answers, curr_nr_of_persons));
-            curr_answers = Vec::new();
-            curr_nr_of_persons = 0;
-            continue;
-        }
-        curr_answers.extend(line.chars());
-        curr_nr_of_persons += 1;
-    }
-
-    if !curr_answers.is_empty() {
-        group_answers.push(GroupAnswer::new(curr_answers, curr_nr_of_persons));
-    }
-
-    group_answers
 }
 
 fn main() {
-    let input: Vec<String> = get_input("./input");
-    let group_answers = parse_input(input);
+    // unwrap is acceptable because of known input
+    let input = BufReader::new(File::open("./input").unwrap());
+
+    let groups = ValidatingParser::new(input, Strictness::Reject)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("report") {
+        print_report(&groups, args.get(1).map(String::as_str));
+        return;
+    }
 
-    let result: usize = group_answers
-        .iter()
-        .map(GroupAnswer::get_mutual_answers)
-        .sum();
+    let anyone: usize = groups.iter().map(GroupAnswer::get_anyone_answers).sum();
+    let everyone: usize = groups.iter().map(GroupAnswer::get_mutual_answers).sum();
 
-    println!("Result: {}", result);
+    println!("Result part 1: {}", anyone);
+    println!("Result part 2: {}", everyone);
 }
@@ -0,0 +1,194 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::answer_set::QUESTIONS;
+use crate::group_answer::GroupAnswer;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct QuestionCount {
+    pub question: char,
+    /// persons answering the question over all groups
+    pub persons: usize,
+    /// groups where anyone answered the question
+    pub groups: usize,
+}
+
+/// Statistics over all groups of a survey.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SurveyReport {
+    pub nr_of_groups: usize,
+    pub nr_of_persons: usize,
+    /// every question, most commonly answered first
+    pub questions: Vec<QuestionCount>,
+    pub most_common: Vec<char>,
+    pub least_common: Vec<char>,
+    /// number of groups per group size
+    pub group_sizes: BTreeMap<usize, usize>,
+    /// questions everyone answered per questions anyone answered, per group
+    pub agreement_ratios: Vec<f64>,
+    pub mean_agreement_ratio: f64,
+    /// groups (starting at 1) without a question everyone answered
+    pub no_agreement: Vec<usize>,
+}
+
+/// 0 for groups where nobody answered anything
+fn agreement_ratio(group: &GroupAnswer) -> f64 {
+    let anyone = group.get_anyone_answers();
+    if anyone == 0 {
+        return 0.0;
+    }
+
+    group.get_mutual_answers() as f64 / anyone as f64
+}
+
+impl SurveyReport {
+    pub fn new(groups: &[GroupAnswer]) -> SurveyReport {
+        let mut questions = QUESTIONS
+            .map(|question| QuestionCount {
+                question,
+                persons: 0,
+                groups: 0,
+            })
+            .collect::<Vec<_>>();
+        let mut group_sizes = BTreeMap::new();
+
+        for group in groups {
+            for (question, persons) in group.histogram() {
+                let count = &mut questions[(question as u8 - b'a') as usize];
+                count.persons += persons;
+                count.groups += 1;
+            }
+
+            *group_sizes.entry(group.nr_of_persons()).or_insert(0) += 1;
+        }
+
+        // stable sort keeps ties in alphabetical order
+        questions.sort_by_key(|q| Reverse(q.persons));
+        let max = questions.first().map_or(0, |q| q.persons);
+        let min = questions.last().map_or(0, |q| q.persons);
+        let with_count = |count: usize| {
+            questions
+                .iter()
+                .filter(|q| q.persons == count)
+                .map(|q| q.question)
+                .collect::<Vec<_>>()
+        };
+        let most_common = with_count(max);
+        let least_common = with_count(min);
+
+        let agreement_ratios = groups.iter().map(agreement_ratio).collect::<Vec<_>>();
+        let mean_agreement_ratio = if groups.is_empty() {
+            0.0
+        } else {
+            agreement_ratios.iter().sum::<f64>() / groups.len() as f64
+        };
+
+        let no_agreement = groups
+            .iter()
+            .enumerate()
+            .filter(|(_, group)| group.get_mutual_answers() == 0)
+            .map(|(i, _)| i + 1)
+            .collect();
+
+        SurveyReport {
+            nr_of_groups: groups.len(),
+            nr_of_persons: groups.iter().map(GroupAnswer::nr_of_persons).sum(),
+            questions,
+            most_common,
+            least_common,
+            group_sizes,
+            agreement_ratios,
+            mean_agreement_ratio,
+            no_agreement,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl fmt::Display for SurveyReport {
+    /// the report as plain text tables, without the ratio of every group
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chars = |questions: &[char]| questions.iter().collect::<String>();
+
+        writeln!(f, "groups:  {}", self.nr_of_groups)?;
+        writeln!(f, "persons: {}", self.nr_of_persons)?;
+        writeln!(f)?;
+
+        writeln!(f, "question | persons | groups")?;
+        writeln!(f, "---------+---------+-------")?;
+        for q in &self.questions {
+            writeln!(f, "{:>8} | {:>7} | {:>6}", q.question, q.persons, q.groups)?;
+        }
+        writeln!(f, "most common:  {}", chars(&self.most_common))?;
+        writeln!(f, "least common: {}", chars(&self.least_common))?;
+        writeln!(f)?;
+
+        writeln!(f, "group size | groups")?;
+        writeln!(f, "-----------+-------")?;
+        for (size, groups) in &self.group_sizes {
+            writeln!(f, "{:>10} | {:>6}", size, groups)?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "mean agreement ratio: {:.3}", self.mean_agreement_ratio)?;
+        writeln!(
+            f,
+            "groups without agreement ({}): {:?}",
+            self.no_agreement.len(),
+            self.no_agreement
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(groups: &[&[&str]]) -> Vec<GroupAnswer> {
+        groups
+            .iter()
+            .map(|persons| GroupAnswer::new(persons.iter().map(|p| p.chars().collect()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_report() {
+        let groups = groups(&[&["abc"], &["a", "b", "c"], &["ab", "ac"], &["b"]]);
+        let report = SurveyReport::new(&groups);
+
+        assert_eq!(4, report.nr_of_groups);
+        assert_eq!(7, report.nr_of_persons);
+        assert_eq!(
+            QuestionCount {
+                question: 'a',
+                persons: 4,
+                groups: 3
+            },
+            report.questions[0]
+        );
+        assert_eq!(vec!['a', 'b'], report.most_common);
+        assert_eq!(23, report.least_common.len());
+        assert_eq!(
+            vec![(1, 2), (2, 1), (3, 1)],
+            report.group_sizes.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![1.0, 0.0, 1.0 / 3.0, 1.0], report.agreement_ratios);
+        assert!((report.mean_agreement_ratio - 7.0 / 12.0).abs() < 1e-9);
+        assert_eq!(vec![2], report.no_agreement);
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = SurveyReport::new(&[]);
+
+        assert_eq!(0.0, report.mean_agreement_ratio);
+        assert_eq!(26, report.most_common.len());
+        assert!(report.to_json().unwrap().contains("\"nr_of_groups\": 0"));
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+use records::{NumberedRecords, ReadRecords, Record};
+
+use crate::answer_set::{AnswerSet, QUESTIONS};
+use crate::group_answer::GroupAnswer;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Strictness {
+    /// every deviation from the puzzle format is an error
+    Reject,
+    /// uppercase answers are lowercased, other chars, duplicates, persons
+    /// without any answer left and empty groups are skipped
+    Normalize,
+}
+
+/// Line and column numbers start at 1.
+#[derive(Debug)]
+pub enum AnswerError {
+    Io(io::Error),
+    InvalidAnswer { line: usize, column: usize, c: char },
+    DuplicateAnswer { line: usize, column: usize, c: char },
+    EmptyGroup { line: usize },
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerError::Io(e) => write!(f, "io error: {}", e),
+            AnswerError::InvalidAnswer { line, column, c } => write!(
+                f,
+                "{}:{}: '{}' is not a question, expected a..=z",
+                line, column, c
+            ),
+            AnswerError::DuplicateAnswer { line, column, c } => {
+                write!(f, "{}:{}: '{}' was already answered", line, column, c)
+            }
+            AnswerError::EmptyGroup { line } => {
+                write!(f, "{}: empty group, too many blank lines", line)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for AnswerError {
+    fn from(e: io::Error) -> Self {
+        AnswerError::Io(e)
+    }
+}
+
+/// Parses groups like `group_answer::parse_input` but checks every answer.
+pub struct ValidatingParser<R> {
+    records: NumberedRecords<R>,
+    strictness: Strictness,
+    /// where the next group starts if it follows a single blank line
+    next_line: usize,
+    /// a group read before reporting the empty group in front of it
+    pending: Option<Record>,
+}
+
+impl<R: BufRead> ValidatingParser<R> {
+    pub fn new(input: R, strictness: Strictness) -> ValidatingParser<R> {
+        ValidatingParser {
+            records: input.numbered_records(),
+            strictness,
+            next_line: 1,
+            pending: None,
+        }
+    }
+
+    fn parse_person(&self, line_nr: usize, line: &str) -> Result<AnswerSet, AnswerError> {
+        let mut answers = AnswerSet::new();
+
+        for (i, c) in line.chars().enumerate() {
+            let c = match self.strictness {
+                Strictness::Reject => c,
+                Strictness::Normalize => c.to_ascii_lowercase(),
+            };
+            let column = i + 1;
+
+            if !QUESTIONS.contains(&c) {
+                if self.strictness == Strictness::Reject {
+                    return Err(AnswerError::InvalidAnswer {
+                        line: line_nr,
+                        column,
+                        c,
+                    });
+                }
+                continue;
+            }
+
+            if !answers.insert(c) && self.strictness == Strictness::Reject {
+                return Err(AnswerError::DuplicateAnswer {
+                    line: line_nr,
+                    column,
+                    c,
+                });
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+impl<R: BufRead> Iterator for ValidatingParser<R> {
+    type Item = Result<GroupAnswer, AnswerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.pending.take().map(Ok).or_else(|| self.records.next()) {
+                Some(Ok(record)) => record,
+                Some(Err(e)) => return Some(Err(e.into())),
+                None => {
+                    // blank lines after the last group
+                    if self.records.lines_read() >= self.next_line
+                        && self.strictness == Strictness::Reject
+                    {
+                        let line = self.next_line;
+                        self.next_line = usize::MAX;
+                        return Some(Err(AnswerError::EmptyGroup { line }));
+                    }
+                    return None;
+                }
+            };
+
+            let line = self.next_line;
+            self.next_line = record.line + record.lines.len() + 1;
+            if record.line > line && self.strictness == Strictness::Reject {
+                self.pending = Some(record);
+                return Some(Err(AnswerError::EmptyGroup { line }));
+            }
+
+            let mut persons = Vec::new();
+            for (i, line) in record.lines.iter().enumerate() {
+                match self.parse_person(record.line + i, line) {
+                    // only a normalised line can end up without answers
+                    Ok(person) if person.is_empty() => {}
+                    Ok(person) => persons.push(person),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
+            if !persons.is_empty() {
+                return Some(Ok(GroupAnswer::new(persons)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str, strictness: Strictness) -> Result<Vec<String>, AnswerError> {
+        ValidatingParser::new(input.as_bytes(), strictness)
+            .map(|group| group.map(|g| g.union().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_input() {
+        let input = "abc\r\n\r\na\nb\n\nab\nac\n";
+        for strictness in [Strictness::Reject, Strictness::Normalize].iter() {
+            assert_eq!(vec!["abc", "ab", "abc"], parse(input, *strictness).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_reject() {
+        let result = parse("ab\nbA\n", Strictness::Reject);
+        assert!(matches!(
+            result,
+            Err(AnswerError::InvalidAnswer {
+                line: 2,
+                column: 2,
+                c: 'A'
+            })
+        ));
+
+        let result = parse("ab\n\nbcb\n", Strictness::Reject);
+        assert!(matches!(
+            result,
+            Err(AnswerError::DuplicateAnswer {
+                line: 3,
+                column: 3,
+                c: 'b'
+            })
+        ));
+
+        let result = parse("ab\n\n\nbc\n", Strictness::Reject);
+        assert!(matches!(result, Err(AnswerError::EmptyGroup { line: 3 })));
+
+        let result = parse("\nab\n", Strictness::Reject);
+        assert!(matches!(result, Err(AnswerError::EmptyGroup { line: 1 })));
+
+        let result = parse("ab\n\n\n", Strictness::Reject);
+        assert!(matches!(result, Err(AnswerError::EmptyGroup { line: 3 })));
+        assert!(parse("ab\n\n", Strictness::Reject).is_ok());
+    }
+
+    #[test]
+    fn test_reject_continues_after_error() {
+        let mut parser = ValidatingParser::new("ab\n\n\nbc\n\nde\n".as_bytes(), Strictness::Reject)
+            .map(|group| group.map(|g| g.union().to_string()));
+
+        assert_eq!("ab", parser.next().unwrap().unwrap());
+        assert!(matches!(
+            parser.next(),
+            Some(Err(AnswerError::EmptyGroup { line: 3 }))
+        ));
+        assert_eq!("bc", parser.next().unwrap().unwrap());
+        assert_eq!("de", parser.next().unwrap().unwrap());
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let groups =
+            ValidatingParser::new("aB1a\nb\n\n\n\nc d\n".as_bytes(), Strictness::Normalize)
+                .map(Result::unwrap)
+                .collect::<Vec<_>>();
+
+        assert_eq!(2, groups.len());
+        assert_eq!(2, groups[0].nr_of_persons());
+        assert_eq!("ab", groups[0].persons()[0].to_string());
+        assert_eq!("b", groups[0].intersection().to_string());
+        assert_eq!("cd", groups[1].union().to_string());
+    }
+
+    #[test]
+    fn test_normalize_persons_without_answers() {
+        let groups = ValidatingParser::new(
+            "ab\n123\nab\n\n1\n-\n\nc\n".as_bytes(),
+            Strictness::Normalize,
+        )
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+
+        assert_eq!(2, groups.len());
+        assert_eq!(2, groups[0].nr_of_persons());
+        assert_eq!("ab", groups[0].intersection().to_string());
+        assert_eq!("c", groups[1].union().to_string());
+    }
+}
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+use crate::parser::{parse_rule, ParseError};
+
+#[derive(Debug)]
+pub struct BagDefinition {
+    pub color: String,
+    pub inner_bags: Vec<(u32, BagDefinition)>,
+}
+
+impl BagDefinition {
+    pub fn new(color: String) -> BagDefinition {
+        BagDefinition::with_inner_bags(color, Vec::new())
+    }
+
+    pub fn with_inner_bags(color: String, inner_bags: Vec<(u32, BagDefinition)>) -> BagDefinition {
+        BagDefinition { color, inner_bags }
+    }
+}
+
+impl FromStr for BagDefinition {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_rule(s, 1)
+    }
+}
+
+/// Parses one rule per line, errors point to the line of the rule.
+pub fn parse_input(input: Vec<String>) -> Result<Vec<BagDefinition>, ParseError> {
+    input
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse_rule(line, i + 1))
+        .collect()
+}
@@ -0,0 +1,343 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::bag_definition::BagDefinition;
+
+pub type ColorId = usize;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum CountError {
+    UnknownColor(String),
+    Cycle(String),
+    Overflow(String),
+}
+
+impl fmt::Display for CountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountError::UnknownColor(color) => write!(f, "no rule for {} bags", color),
+            CountError::Cycle(color) => write!(f, "{} bags contain themselves", color),
+            CountError::Overflow(color) => {
+                write!(f, "{} bags contain more than {} bags", color, u64::MAX)
+            }
+        }
+    }
+}
+
+/// The bag rules as a graph with edges in both directions.
+///
+/// Every color is interned into a `ColorId` which indexes the adjacency lists,
+/// so no lookup has to scan all rules.
+#[derive(Debug, Default)]
+pub struct BagGraph {
+    colors: Vec<String>,
+    ids: HashMap<String, ColorId>,
+    /// whether a rule for the bag exists or it's only referenced as inner bag
+    has_rule: Vec<bool>,
+    /// (inner bag, count) per bag
+    contains: Vec<Vec<(ColorId, u32)>>,
+    /// (outer bag, count) per bag
+    contained_by: Vec<Vec<(ColorId, u32)>>,
+    /// cache of `total_contained` per bag
+    totals: RefCell<Vec<Option<u64>>>,
+}
+
+impl BagGraph {
+    pub fn new(bags: &[BagDefinition]) -> BagGraph {
+        let mut graph = BagGraph::default();
+
+        for bag in bags {
+            let outer = graph.intern(&bag.color);
+            graph.has_rule[outer] = true;
+            for (count, inner_bag) in &bag.inner_bags {
+                let inner = graph.intern(&inner_bag.color);
+                graph.contains[outer].push((inner, *count));
+                graph.contained_by[inner].push((outer, *count));
+            }
+        }
+
+        graph
+    }
+
+    fn intern(&mut self, color: &str) -> ColorId {
+        if let Some(id) = self.ids.get(color) {
+            return *id;
+        }
+
+        let id = self.colors.len();
+        self.colors.push(String::from(color));
+        self.ids.insert(String::from(color), id);
+        self.has_rule.push(false);
+        self.contains.push(Vec::new());
+        self.contained_by.push(Vec::new());
+
+        id
+    }
+
+    pub fn id(&self, color: &str) -> Option<ColorId> {
+        self.ids.get(color).copied()
+    }
+
+    pub fn color(&self, id: ColorId) -> &str {
+        &self.colors[id]
+    }
+
+    pub fn has_rule(&self, id: ColorId) -> bool {
+        self.has_rule[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = ColorId> {
+        0..self.len()
+    }
+
+    /// the bags directly inside of `id` with their count
+    pub fn contains(&self, id: ColorId) -> &[(ColorId, u32)] {
+        &self.contains[id]
+    }
+
+    /// the bags directly holding `id` with the count of `id` inside them
+    pub fn contained_by(&self, id: ColorId) -> &[(ColorId, u32)] {
+        &self.contained_by[id]
+    }
+
+    /// every bag that eventually holds `id`
+    pub fn ancestors(&self, id: ColorId) -> HashSet<ColorId> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![id];
+
+        while let Some(curr) = stack.pop() {
+            for (outer, _count) in self.contained_by(curr) {
+                if visited.insert(*outer) {
+                    stack.push(*outer);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// every bag eventually inside of `id`
+    pub fn descendants(&self, id: ColorId) -> HashSet<ColorId> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![id];
+
+        while let Some(curr) = stack.pop() {
+            for (inner, _count) in self.contains(curr) {
+                if visited.insert(*inner) {
+                    stack.push(*inner);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Number of bags inside of `color`.
+    ///
+    /// The totals of all bags visited are cached, so every sub-tree is only
+    /// counted once, even over multiple calls.
+    pub fn total_contained(&self, color: &str) -> Result<u64, CountError> {
+        let id = self
+            .id(color)
+            .ok_or_else(|| CountError::UnknownColor(String::from(color)))?;
+
+        let mut totals = self.totals.borrow_mut();
+        totals.resize(self.len(), None);
+
+        self.fold_contained(id, &mut totals, 0, |curr, total, inner_total, count| {
+            // the inner bag itself and everything inside of it
+            inner_total
+                .checked_add(1)
+                .and_then(|bags| bags.checked_mul(u64::from(count)))
+                .and_then(|bags| bags.checked_add(total))
+                .ok_or_else(|| CountError::Overflow(String::from(self.color(curr))))
+        })
+    }
+
+    /// Computes a value for `id` from the values of the bags directly inside
+    /// of it, bottom up and without recursion.
+    ///
+    /// Every bag starts with `init` and `combine` adds the value and count of
+    /// one inner bag to it. Values are stored in `values`, so a bag already
+    /// there isn't visited again.
+    pub(crate) fn fold_contained<T, F>(
+        &self,
+        id: ColorId,
+        values: &mut [Option<T>],
+        init: T,
+        mut combine: F,
+    ) -> Result<T, CountError>
+    where
+        T: Copy,
+        F: FnMut(ColorId, T, T, u32) -> Result<T, CountError>,
+    {
+        if let Some(value) = values[id] {
+            return Ok(value);
+        }
+
+        let mut in_progress = vec![false; self.len()];
+        // the bags from `id` to the current one with the index of their next
+        // inner bag and their value so far
+        let mut path: Vec<(ColorId, usize, T)> = Vec::new();
+        let mut next = Some(id);
+
+        loop {
+            if let Some(id) = next.take() {
+                let color = || String::from(self.color(id));
+                if !self.has_rule(id) {
+                    return Err(CountError::UnknownColor(color()));
+                }
+                if in_progress[id] {
+                    return Err(CountError::Cycle(color()));
+                }
+                in_progress[id] = true;
+                path.push((id, 0, init));
+            }
+
+            // unwrap is acceptable because the loop ends when the path is empty
+            let (curr, edge, value) = path.last_mut().unwrap();
+            match self.contains(*curr).get(*edge) {
+                Some(&(inner, count)) => {
+                    *edge += 1;
+                    match values[inner] {
+                        Some(inner_value) => *value = combine(*curr, *value, inner_value, count)?,
+                        None => next = Some(inner),
+                    }
+                }
+                None => {
+                    let (curr, _edge, value) = path.pop().unwrap();
+                    in_progress[curr] = false;
+                    values[curr] = Some(value);
+
+                    match path.last_mut() {
+                        Some((outer, edge, outer_value)) => {
+                            let (_inner, count) = self.contains(*outer)[*edge - 1];
+                            *outer_value = combine(*outer, *outer_value, value, count)?;
+                        }
+                        None => return Ok(value),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{chain, parse_graph};
+
+    const EXAMPLE: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    const CHAIN: &str = "shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.";
+
+    #[test]
+    fn test_graph() {
+        let graph = parse_graph(EXAMPLE);
+        let shiny_gold = graph.id("shiny gold").unwrap();
+        let muted_yellow = graph.id("muted yellow").unwrap();
+
+        assert_eq!(9, graph.len());
+        assert_eq!("shiny gold", graph.color(shiny_gold));
+        assert_eq!(None, graph.id("mauve"));
+        assert_eq!(2, graph.contains(shiny_gold).len());
+        assert_eq!(&[(muted_yellow, 2)], &graph.contained_by(shiny_gold)[1..]);
+    }
+
+    #[test]
+    fn test_puzzle_parts() {
+        let graph = parse_graph(EXAMPLE);
+        let shiny_gold = graph.id("shiny gold").unwrap();
+
+        assert_eq!(4, graph.ancestors(shiny_gold).len());
+        assert_eq!(4, graph.descendants(shiny_gold).len());
+        assert_eq!(Ok(32), graph.total_contained("shiny gold"));
+        // served from the cache
+        assert_eq!(Ok(32), graph.total_contained("shiny gold"));
+        assert_eq!(Ok(7), graph.total_contained("dark olive"));
+
+        let graph = parse_graph(CHAIN);
+        assert_eq!(Ok(126), graph.total_contained("shiny gold"));
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let graph = parse_graph(&chain(100_000));
+
+        assert_eq!(Ok(99_999), graph.total_contained("shade0 blue"));
+        assert_eq!(Ok(49_999), graph.total_contained("shade50000 blue"));
+    }
+
+    #[test]
+    fn test_ancestors_with_cycle() {
+        let graph = parse_graph(
+            "light red bags contain 1 bright white bag.
+bright white bags contain 1 shiny gold bag, 1 light red bag.
+shiny gold bags contain no other bags.",
+        );
+        let ancestors = graph.ancestors(graph.id("shiny gold").unwrap());
+
+        assert_eq!(2, ancestors.len());
+        assert!(ancestors.contains(&graph.id("light red").unwrap()));
+        assert!(ancestors.contains(&graph.id("bright white").unwrap()));
+    }
+
+    #[test]
+    fn test_total_contained_errors() {
+        let graph = parse_graph(EXAMPLE);
+        assert_eq!(
+            Err(CountError::UnknownColor(String::from("mauve"))),
+            graph.total_contained("mauve")
+        );
+
+        let graph = parse_graph("shiny gold bags contain 1 dark red bag.");
+        assert_eq!(
+            Err(CountError::UnknownColor(String::from("dark red"))),
+            graph.total_contained("shiny gold")
+        );
+
+        let graph = parse_graph(
+            "shiny gold bags contain 1 dark red bag.
+dark red bags contain 2 shiny gold bags.",
+        );
+        assert_eq!(
+            Err(CountError::Cycle(String::from("shiny gold"))),
+            graph.total_contained("shiny gold")
+        );
+
+        let graph = parse_graph(
+            "shiny gold bags contain 4294967295 dark red bags.
+dark red bags contain 4294967295 dark orange bags.
+dark orange bags contain 4294967295 dark yellow bags.
+dark yellow bags contain no other bags.",
+        );
+        assert!(graph.total_contained("dark red").is_ok());
+        assert_eq!(
+            Err(CountError::Overflow(String::from("shiny gold"))),
+            graph.total_contained("shiny gold")
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::bag_graph::{BagGraph, ColorId};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Format {
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format = match s {
+            "dot" => Format::Dot,
+            "mermaid" => Format::Mermaid,
+            _ => return Err(()),
+        };
+
+        Ok(format)
+    }
+}
+
+/// The part of the graph to export, the chosen bag is always included.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Subgraph {
+    All,
+    /// the bag and every bag eventually inside of it
+    ReachableFrom(ColorId),
+    /// the bag and every bag eventually holding it
+    Reaching(ColorId),
+}
+
+impl Subgraph {
+    fn bags(self, graph: &BagGraph) -> HashSet<ColorId> {
+        let (id, mut bags) = match self {
+            Subgraph::All => return graph.ids().collect(),
+            Subgraph::ReachableFrom(id) => (id, graph.descendants(id)),
+            Subgraph::Reaching(id) => (id, graph.ancestors(id)),
+        };
+        bags.insert(id);
+
+        bags
+    }
+}
+
+fn quote(color: &str) -> String {
+    format!("\"{}\"", color.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Renders the bags of `subgraph` with an edge from every bag to the bags
+/// directly inside of it, labelled with their count. Edges leaving the
+/// subgraph are left out.
+///
+/// Bags are written in the order of their first appearance in the rules.
+pub fn export(graph: &BagGraph, format: Format, subgraph: Subgraph) -> String {
+    let selected = subgraph.bags(graph);
+    let bags = graph
+        .ids()
+        .filter(|id| selected.contains(id))
+        .collect::<Vec<_>>();
+    let edges = |outer: ColorId| {
+        graph
+            .contains(outer)
+            .iter()
+            .filter(|(inner, _count)| selected.contains(inner))
+    };
+    let mut out = String::new();
+
+    // writing into a String never fails
+    match format {
+        Format::Dot => {
+            out.push_str("digraph bags {\n");
+            for &id in &bags {
+                writeln!(out, "    {};", quote(graph.color(id))).unwrap();
+            }
+            for &outer in &bags {
+                for (inner, count) in edges(outer) {
+                    writeln!(
+                        out,
+                        "    {} -> {} [label=\"{}\"];",
+                        quote(graph.color(outer)),
+                        quote(graph.color(*inner)),
+                        count
+                    )
+                    .unwrap();
+                }
+            }
+            out.push_str("}\n");
+        }
+        Format::Mermaid => {
+            // colors contain spaces, so bags are referenced by their id
+            out.push_str("graph TD\n");
+            for &id in &bags {
+                writeln!(out, "    bag{}[{}]", id, quote(graph.color(id))).unwrap();
+            }
+            for &outer in &bags {
+                for (inner, count) in edges(outer) {
+                    writeln!(out, "    bag{} -->|{}| bag{}", outer, count, inner).unwrap();
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse_graph;
+
+    const RULES: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags.
+shiny gold bags contain 3 faded blue bags.
+faded blue bags contain no other bags.";
+
+    #[test]
+    fn test_dot() {
+        let graph = parse_graph(RULES);
+        let shiny_gold = graph.id("shiny gold").unwrap();
+
+        assert_eq!(
+            "digraph bags {
+    \"shiny gold\";
+    \"faded blue\";
+    \"shiny gold\" -> \"faded blue\" [label=\"3\"];
+}
+",
+            export(&graph, Format::Dot, Subgraph::ReachableFrom(shiny_gold))
+        );
+
+        let dot = export(&graph, Format::Dot, Subgraph::All);
+        assert_eq!(5 + 5 + 2, dot.lines().count());
+        assert!(dot.contains("\"light red\" -> \"muted yellow\" [label=\"2\"];"));
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let graph = parse_graph(RULES);
+        let bright_white = graph.id("bright white").unwrap();
+
+        assert_eq!(
+            "graph TD
+    bag0[\"light red\"]
+    bag1[\"bright white\"]
+    bag0 -->|1| bag1
+",
+            export(&graph, Format::Mermaid, Subgraph::Reaching(bright_white))
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use std::io::{self, BufRead};
+use std::{fmt::Debug, fs::File, path::Path, str::FromStr};
+
+pub mod bag_definition;
+pub mod bag_graph;
+pub mod export;
+pub mod parser;
+pub mod queries;
+#[cfg(test)]
+mod test_util;
+pub mod validation;
+
+fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(filename)?;
+    Ok(io::BufReader::new(file).lines())
+}
+
+pub fn get_input<T>(file: &str) -> Vec<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Debug,
+{
+    // unwrap is acceptable because of known input
+    read_lines(file)
+        .unwrap()
+        .map(|line| line.unwrap().parse::<T>().unwrap())
+        .collect::<Vec<_>>()
+}
@@ -1,177 +1,121 @@
-use std::{collections::HashSet, io::{self, BufRead}, thread::current};
-use std::{fmt::Debug, fs::File, path::Path, str::FromStr};
+use std::{env, process};
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-fn get_input<T>(file: &str) -> Vec<T>
-where
-    T: FromStr,
-    <T as FromStr>::Err: Debug,
-{
-    // unwrap is acceptable because of known input
-    read_lines(file)
-        .unwrap()
-        .map(|line| line.unwrap().parse::<T>().unwrap())
-        .collect::<Vec<_>>()
-}
+use day_07::bag_definition::parse_input;
+use day_07::bag_graph::{BagGraph, ColorId};
+use day_07::export::{export, Format, Subgraph};
+use day_07::queries::{containment_paths, leaves, max_depth, roots, topological_order};
+use day_07::validation::validate;
 
-struct BagDefinition {
-    color: String,
-    inner_bags: Vec<(u32, BagDefinition)>,
+fn export_usage() -> ! {
+    eprintln!("usage: day_07 export <dot|mermaid> [from|to <color>]");
+    process::exit(1);
 }
 
-impl BagDefinition {
-    pub fn new(color: String) -> BagDefinition {
-        BagDefinition::with_inner_bags(color, Vec::new())
-    }
-
-    pub fn with_inner_bags(color: String, inner_bags: Vec<(u32, BagDefinition)>) -> BagDefinition {
-        BagDefinition { color, inner_bags }
-    }
-}
-
-fn parse_inner_bag_definition(input: &str) -> Option<(u32, BagDefinition)> {
-    let mut input = input.trim().split(' ');
-    // input == "no other bags"
-    if input.clone().count() == 3 {
-        return None;
-    }
-
-    let count = input.next().unwrap().parse().unwrap();
-    let color = format!("{} {}", input.next().unwrap(), input.next().unwrap());
-    let bag = BagDefinition::new(color);
-
-    Some((count, bag))
-}
-
-fn parse_child_bag_definitions(input: &str) -> Vec<(u32, BagDefinition)> {
-    let bag_definitions = input.strip_suffix('.').unwrap().split(',');
+/// usage: day_07 export <dot|mermaid> [from|to <color>]
+///
+/// colors with several words have to be quoted
+fn print_export(graph: &BagGraph, args: &[String]) {
+    let format = match args.first().map(|f| f.parse::<Format>()) {
+        Some(Ok(format)) => format,
+        _ => export_usage(),
+    };
+    let subgraph = match &args[1.min(args.len())..] {
+        [] => Subgraph::All,
+        [direction, color] if direction == "from" => {
+            Subgraph::ReachableFrom(color_id(graph, color))
+        }
+        [direction, color] if direction == "to" => Subgraph::Reaching(color_id(graph, color)),
+        _ => export_usage(),
+    };
 
-    bag_definitions
-        .map(parse_inner_bag_definition)
-        .flatten()
-        .collect()
+    print!("{}", export(graph, format, subgraph));
 }
 
-/*
-BagDefiniton
-: Color "bags contain" ContentDefinition
-;
-
-ContentDefinition
-: NoContent
-| MultipleContent* SingleContent
-;
-
-SingleContent
-: number Color "bag."
-;
-
-MultipleContent
-: number Color "bags,"
-;
-
-Color
-: string string
-;
-
-NoContent
-: "no other bags"
-;
-*/
-fn parse_bag_definition(bag_definition: String) -> BagDefinition {
-    let mut split = bag_definition.splitn(2, " contain ");
-    let bag = split.next().unwrap();
-
-    // bag must contain exactly 3 elements --> 2 words color 1 word "bags"
-    let mut iter = bag.split(' ');
-    let bag_color = format!("{} {}", iter.next().unwrap(), iter.next().unwrap());
-
-    let inner_bags = split.next().unwrap();
-    let inner_bags = parse_child_bag_definitions(inner_bags);
-
-    BagDefinition::with_inner_bags(bag_color, inner_bags)
+fn query_usage() -> ! {
+    eprintln!("usage: day_07 query <paths <from> <to>|depth <color>|roots|leaves|order>");
+    process::exit(1);
 }
 
-fn parse_input(input: Vec<String>) -> Vec<BagDefinition> {
-    input.into_iter().map(parse_bag_definition).collect()
+fn color_id(graph: &BagGraph, color: &str) -> ColorId {
+    graph.id(color).unwrap_or_else(|| {
+        eprintln!("no rule for {} bags", color);
+        process::exit(1);
+    })
 }
 
-fn get_curr_parents<'a>(bags: &'a Vec<BagDefinition>, color: &str) -> Vec<&'a str> {
-    bags.iter()
-        .filter(|bag| {
-            bag.inner_bags
-                .iter()
-                .any(|(_count, inner_bag)| inner_bag.color == color)
-        })
-        .map(|bag| &bag.color[..])
-        .collect()
+fn print_colors(graph: &BagGraph, ids: &[ColorId]) {
+    for id in ids {
+        println!("{}", graph.color(*id));
+    }
 }
 
-fn get_parent_bags<'a>(bags: Vec<BagDefinition>, wanted_color: String) -> HashSet<String> {
-    let mut wanted_colors = HashSet::new();
-    let mut curr_colors = vec![&wanted_color[..]];
-    let mut tmp_colors = Vec::new();
-
-    while !curr_colors.is_empty() {
-        for &curr_color in curr_colors.iter() {
-            let mut parents = get_curr_parents(&bags, curr_color);
-            // if children of bags contain curr_color
-            if !parents.is_empty() {
-                wanted_colors.extend(&mut parents.iter().map(|color| String::from(*color)));
-                tmp_colors.append(&mut parents);
+/// usage: day_07 query <paths <from> <to>|depth <color>|roots|leaves|order>
+///
+/// colors with several words have to be quoted
+fn print_query(graph: &BagGraph, args: &[String]) {
+    match (args.first().map(String::as_str), &args[1.min(args.len())..]) {
+        (Some("paths"), [from, to]) => {
+            let from = color_id(graph, from);
+            let to = color_id(graph, to);
+            for path in containment_paths(graph, from, to) {
+                let path = path.iter().map(|id| graph.color(*id)).collect::<Vec<_>>();
+                println!("{}", path.join(" > "));
             }
         }
-        curr_colors.clear();
-        curr_colors.append(&mut tmp_colors);
+        (Some("depth"), [color]) => match max_depth(graph, color_id(graph, color)) {
+            Ok(depth) => println!("{}", depth),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        },
+        (Some("roots"), []) => print_colors(graph, &roots(graph)),
+        (Some("leaves"), []) => print_colors(graph, &leaves(graph)),
+        (Some("order"), []) => match topological_order(graph) {
+            Ok(order) => print_colors(graph, &order),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        },
+        _ => query_usage(),
     }
-
-    wanted_colors
 }
 
-fn count_siblings(bags: &Vec<&BagDefinition>, wanted_color: &str) -> u32 {
-    let current_bag = bags
-        .iter()
-        .filter(|bag| bag.color == wanted_color)
-        .next()
-        .unwrap();
-
-    if current_bag.inner_bags.is_empty() {
-        return 0;
+fn main() {
+    let input: Vec<String> = day_07::get_input("./input");
+    let bag_definitions = match parse_input(input) {
+        Ok(bag_definitions) => bag_definitions,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    let graph = BagGraph::new(&bag_definitions);
+    if let Err(e) = validate(&graph) {
+        eprintln!("{}", e);
+        process::exit(1);
     }
 
-    let mut curr_bag_child_count: u32 = 0;
-
-    for (inner_child_count, child) in current_bag.inner_bags.iter() {
-        let inner_siblings = count_siblings(bags, &child.color);
-        curr_bag_child_count += if inner_siblings == 0 {
-            inner_child_count.clone()
-        } else {
-            inner_child_count * inner_siblings
-        };
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("export") {
+        print_export(&graph, &args[1..]);
+        return;
+    }
+    if args.first().map(String::as_str) == Some("query") {
+        print_query(&graph, &args[1..]);
+        return;
     }
-    
-    // add curr bag
-    curr_bag_child_count + 1
-}
-
-fn main() {
-    let input: Vec<String> = get_input("./input");
-    let bag_definitions = parse_input(input);
-
-    // let bags = get_parent_bags(bag_definitions, String::from("shiny gold"));
-
-    // println!("RESULT: {}", bags.len());
-
-    let siblings = count_siblings(&bag_definitions.iter().collect(), "shiny gold");
-    // counts top bag as sibling --> to lazy to fix
-    let siblings = siblings - 1;
 
-    println!("RESULT: {}", siblings);
+    // unwrap is acceptable because of known input
+    let shiny_gold = graph.id("shiny gold").unwrap();
+
+    println!("RESULT part 1: {}", graph.ancestors(shiny_gold).len());
+    match graph.total_contained("shiny gold") {
+        Ok(total) => println!("RESULT part 2: {}", total),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
 }
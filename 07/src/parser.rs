@@ -0,0 +1,354 @@
+use std::fmt;
+
+use crate::bag_definition::BagDefinition;
+
+/*
+BagDefinition
+: Color Bag "contain" ContentDefinition "."
+;
+
+ContentDefinition
+: NoContent
+| Content ("," Content)*
+;
+
+Content
+: number Color Bag
+;
+
+Color
+: word word*
+;
+
+Bag
+: "bag"
+| "bags"
+;
+
+NoContent
+: "no" "other" Bag
+;
+*/
+
+/// Line and column numbers start at 1.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseErrorKind {
+    Expected {
+        expected: &'static str,
+        found: String,
+    },
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+    InvalidNumber(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::Expected { expected, found } => {
+                write!(f, "expected {}, found '{}'", expected, found)
+            }
+            ParseErrorKind::UnexpectedEnd { expected } => {
+                write!(f, "expected {}, found end of rule", expected)
+            }
+            ParseErrorKind::InvalidNumber(number) => {
+                write!(f, "'{}' is not a valid bag count", number)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Token<'a> {
+    Word(&'a str),
+    Number(&'a str),
+    Comma,
+    Period,
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(s) | Token::Number(s) => write!(f, "{}", s),
+            Token::Comma => write!(f, ","),
+            Token::Period => write!(f, "."),
+        }
+    }
+}
+
+fn word_token(word: &str) -> Token<'_> {
+    if word.chars().all(|c| c.is_ascii_digit()) {
+        Token::Number(word)
+    } else {
+        Token::Word(word)
+    }
+}
+
+/// splits a rule into tokens and their column
+fn tokenize(input: &str) -> Vec<(usize, Token<'_>)> {
+    let mut tokens = Vec::new();
+    let mut word_start: Option<usize> = None;
+
+    for (i, c) in input.char_indices() {
+        if c != ',' && c != '.' && !c.is_whitespace() {
+            word_start.get_or_insert(i);
+            continue;
+        }
+
+        if let Some(start) = word_start.take() {
+            tokens.push((start, word_token(&input[start..i])));
+        }
+        match c {
+            ',' => tokens.push((i, Token::Comma)),
+            '.' => tokens.push((i, Token::Period)),
+            _ => {}
+        }
+    }
+    if let Some(start) = word_start {
+        tokens.push((start, word_token(&input[start..])));
+    }
+
+    // byte offsets into columns
+    tokens
+        .into_iter()
+        .map(|(i, token)| (input[..i].chars().count() + 1, token))
+        .collect()
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token<'a>)>,
+    pos: usize,
+    line: usize,
+    /// column right after the last char, reported at the end of the rule
+    end_column: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).map(|(_, token)| *token)
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        let column = self
+            .tokens
+            .get(self.pos)
+            .map_or(self.end_column, |(column, _)| *column);
+
+        ParseError {
+            line: self.line,
+            column,
+            kind,
+        }
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        match self.peek() {
+            Some(token) => self.error(ParseErrorKind::Expected {
+                expected,
+                found: token.to_string(),
+            }),
+            None => self.error(ParseErrorKind::UnexpectedEnd { expected }),
+        }
+    }
+
+    fn expect(
+        &mut self,
+        expected: Token<'static>,
+        description: &'static str,
+    ) -> Result<(), ParseError> {
+        if self.peek() != Some(expected) {
+            return Err(self.unexpected(description));
+        }
+
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn is_bag(token: Option<Token<'_>>) -> bool {
+        matches!(token, Some(Token::Word("bag")) | Some(Token::Word("bags")))
+    }
+
+    fn bag(&mut self) -> Result<(), ParseError> {
+        if !Parser::is_bag(self.peek()) {
+            return Err(self.unexpected("'bag' or 'bags'"));
+        }
+
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn color(&mut self) -> Result<String, ParseError> {
+        let mut words = Vec::new();
+
+        while let Some(Token::Word(word)) = self.peek() {
+            if Parser::is_bag(self.peek()) {
+                break;
+            }
+            words.push(word);
+            self.pos += 1;
+        }
+
+        if words.is_empty() {
+            return Err(self.unexpected("a color"));
+        }
+
+        Ok(words.join(" "))
+    }
+
+    fn content(&mut self) -> Result<(u32, BagDefinition), ParseError> {
+        let count = match self.peek() {
+            Some(Token::Number(number)) => number
+                .parse()
+                .map_err(|_| self.error(ParseErrorKind::InvalidNumber(String::from(number))))?,
+            _ => return Err(self.unexpected("a bag count")),
+        };
+        self.pos += 1;
+
+        let color = self.color()?;
+        self.bag()?;
+
+        Ok((count, BagDefinition::new(color)))
+    }
+
+    fn content_definition(&mut self) -> Result<Vec<(u32, BagDefinition)>, ParseError> {
+        if self.peek() == Some(Token::Word("no")) {
+            self.pos += 1;
+            self.expect(Token::Word("other"), "'other'")?;
+            self.bag()?;
+
+            return Ok(Vec::new());
+        }
+
+        let mut inner_bags = vec![self.content()?];
+        while self.peek() == Some(Token::Comma) {
+            self.pos += 1;
+            inner_bags.push(self.content()?);
+        }
+
+        Ok(inner_bags)
+    }
+
+    fn bag_definition(&mut self) -> Result<BagDefinition, ParseError> {
+        let color = self.color()?;
+        self.bag()?;
+        self.expect(Token::Word("contain"), "'contain'")?;
+        let inner_bags = self.content_definition()?;
+        self.expect(Token::Period, "'.'")?;
+
+        if self.peek().is_some() {
+            return Err(self.unexpected("end of rule"));
+        }
+
+        Ok(BagDefinition::with_inner_bags(color, inner_bags))
+    }
+}
+
+/// Parses a single rule, `line` is only used for error positions.
+pub fn parse_rule(input: &str, line: usize) -> Result<BagDefinition, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input),
+        pos: 0,
+        line,
+        end_column: input.chars().count() + 1,
+    };
+
+    parser.bag_definition()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inner_bags(bag: &BagDefinition) -> Vec<(u32, &str)> {
+        bag.inner_bags
+            .iter()
+            .map(|(count, inner)| (*count, inner.color.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let bag = parse_rule(
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.",
+            1,
+        )
+        .unwrap();
+        assert_eq!("light red", bag.color);
+        assert_eq!(
+            vec![(1, "bright white"), (2, "muted yellow")],
+            inner_bags(&bag)
+        );
+
+        let bag = parse_rule("faded blue bags contain no other bags.", 1).unwrap();
+        assert_eq!("faded blue", bag.color);
+        assert!(bag.inner_bags.is_empty());
+    }
+
+    #[test]
+    fn test_color_lengths() {
+        let bag = parse_rule(
+            "red bag contain 1 dark red bag, 12 very pale dark blue bags.",
+            1,
+        )
+        .unwrap();
+        assert_eq!("red", bag.color);
+        assert_eq!(
+            vec![(1, "dark red"), (12, "very pale dark blue")],
+            inner_bags(&bag)
+        );
+
+        let bag = parse_rule("very  dark\tred bags contain 1 red bag .", 1).unwrap();
+        assert_eq!("very dark red", bag.color);
+        assert_eq!(vec![(1, "red")], inner_bags(&bag));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |input| parse_rule(input, 3).unwrap_err();
+
+        assert_eq!(
+            ParseError {
+                line: 3,
+                column: 16,
+                kind: ParseErrorKind::Expected {
+                    expected: "'contain'",
+                    found: String::from("hold")
+                }
+            },
+            error("light red bags hold 1 bright white bag.")
+        );
+        assert_eq!(
+            "3:24: expected a bag count, found 'bright'",
+            error("light red bags contain bright white bag.").to_string()
+        );
+        assert_eq!(
+            "3:34: expected 'bag' or 'bags', found ','",
+            error("light red bags contain 1 dark red, 2 blue bags.").to_string()
+        );
+        assert_eq!(
+            "3:38: expected '.', found end of rule",
+            error("faded blue bags contain no other bags").to_string()
+        );
+        assert_eq!(
+            "3:1: expected a color, found 'bags'",
+            error("bags contain no other bags.").to_string()
+        );
+        assert_eq!(
+            "3:24: '99999999999' is not a valid bag count",
+            error("light red bags contain 99999999999 red bags.").to_string()
+        );
+        assert_eq!(
+            "3:40: expected end of rule, found 'extra'",
+            error("faded blue bags contain no other bags. extra").to_string()
+        );
+    }
+}
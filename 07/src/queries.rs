@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+
+use crate::bag_graph::{BagGraph, ColorId, CountError};
+use crate::validation::{find_cycle, RuleError};
+
+/// Every way `to` is nested inside of `from`, as the bags from the outermost
+/// to the innermost one.
+///
+/// Paths never visit a bag twice, so cycles don't produce endless paths.
+pub fn containment_paths(graph: &BagGraph, from: ColorId, to: ColorId) -> Vec<Vec<ColorId>> {
+    let mut paths = Vec::new();
+    let mut path = vec![from];
+    let mut on_path = vec![false; graph.len()];
+    on_path[from] = true;
+
+    // depth first search without recursion, `next_edge` holds the index of
+    // the next inner bag to visit for every bag on the path
+    let mut next_edge = vec![0];
+
+    while let Some(&curr) = path.last() {
+        // unwrap is acceptable because there is an index for every bag on the path
+        let edge = next_edge.last_mut().unwrap();
+
+        match graph.contains(curr).get(*edge) {
+            Some(&(inner, _count)) => {
+                *edge += 1;
+                if inner == to {
+                    let mut found = path.clone();
+                    found.push(to);
+                    paths.push(found);
+                } else if !on_path[inner] {
+                    on_path[inner] = true;
+                    path.push(inner);
+                    next_edge.push(0);
+                }
+            }
+            None => {
+                on_path[curr] = false;
+                path.pop();
+                next_edge.pop();
+            }
+        }
+    }
+
+    paths
+}
+
+/// Number of bag levels below `id`, 0 for bags containing no other bags.
+pub fn max_depth(graph: &BagGraph, id: ColorId) -> Result<u32, CountError> {
+    let mut depths = vec![None; graph.len()];
+
+    graph.fold_contained(id, &mut depths, 0, |_id, max, depth, _count| {
+        Ok(max.max(depth + 1))
+    })
+}
+
+/// bags no other bag contains
+pub fn roots(graph: &BagGraph) -> Vec<ColorId> {
+    graph
+        .ids()
+        .filter(|id| graph.contained_by(*id).is_empty())
+        .collect()
+}
+
+/// bags with a rule saying they contain no other bags
+pub fn leaves(graph: &BagGraph) -> Vec<ColorId> {
+    graph
+        .ids()
+        .filter(|id| graph.has_rule(*id) && graph.contains(*id).is_empty())
+        .collect()
+}
+
+/// All bags ordered so every bag comes before the bags inside of it.
+///
+/// Ties keep the order of the rules.
+pub fn topological_order(graph: &BagGraph) -> Result<Vec<ColorId>, RuleError> {
+    let mut outer_bags = graph
+        .ids()
+        .map(|id| graph.contained_by(id).len())
+        .collect::<Vec<_>>();
+    let mut ready = graph
+        .ids()
+        .filter(|id| outer_bags[*id] == 0)
+        .collect::<VecDeque<_>>();
+    let mut order = Vec::with_capacity(graph.len());
+
+    while let Some(id) = ready.pop_front() {
+        order.push(id);
+        for (inner, _count) in graph.contains(id) {
+            outer_bags[*inner] -= 1;
+            if outer_bags[*inner] == 0 {
+                ready.push_back(*inner);
+            }
+        }
+    }
+
+    if order.len() < graph.len() {
+        // unwrap is acceptable because bags are only left over on cycles
+        let cycle = find_cycle(graph).unwrap();
+        let cycle = cycle.iter().map(|id| String::from(graph.color(*id)));
+        return Err(RuleError::Cycle(cycle.collect()));
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{chain, parse_graph};
+    use crate::validation::validate;
+
+    const RULES: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 3 faded blue bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+
+    fn colors(graph: &BagGraph, ids: &[ColorId]) -> Vec<String> {
+        ids.iter()
+            .map(|id| String::from(graph.color(*id)))
+            .collect()
+    }
+
+    #[test]
+    fn test_containment_paths() {
+        let graph = parse_graph(RULES);
+        let id = |color| graph.id(color).unwrap();
+
+        let paths = containment_paths(&graph, id("light red"), id("faded blue"))
+            .iter()
+            .map(|path| colors(&graph, path).join(" > "))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "light red > bright white > shiny gold > faded blue",
+                "light red > muted yellow > shiny gold > faded blue",
+                "light red > muted yellow > faded blue",
+            ],
+            paths
+        );
+
+        assert!(containment_paths(&graph, id("faded blue"), id("light red")).is_empty());
+        assert!(containment_paths(&graph, id("shiny gold"), id("shiny gold")).is_empty());
+    }
+
+    #[test]
+    fn test_containment_paths_with_cycle() {
+        let graph = parse_graph(
+            "light red bags contain 1 bright white bag.
+bright white bags contain 1 light red bag, 1 shiny gold bag.
+shiny gold bags contain no other bags.",
+        );
+        let id = |color| graph.id(color).unwrap();
+
+        assert_eq!(
+            vec![vec![id("light red"), id("bright white"), id("shiny gold")]],
+            containment_paths(&graph, id("light red"), id("shiny gold"))
+        );
+        assert_eq!(
+            vec![vec![id("light red"), id("bright white"), id("light red")]],
+            containment_paths(&graph, id("light red"), id("light red"))
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let graph = parse_graph(RULES);
+        let id = |color| graph.id(color).unwrap();
+
+        assert_eq!(Ok(3), max_depth(&graph, id("light red")));
+        assert_eq!(Ok(1), max_depth(&graph, id("shiny gold")));
+        assert_eq!(Ok(0), max_depth(&graph, id("faded blue")));
+
+        let graph = parse_graph("shiny gold bags contain 1 shiny gold bag.");
+        assert_eq!(
+            Err(CountError::Cycle(String::from("shiny gold"))),
+            max_depth(&graph, 0)
+        );
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // deeper than the stack of a test thread allows for recursion
+        let graph = parse_graph(&chain(100_000));
+        let first = graph.id("shade0 blue").unwrap();
+        let last = graph.id("shade99999 blue").unwrap();
+
+        assert!(validate(&graph).is_ok());
+        assert_eq!(Ok(99_999), max_depth(&graph, first));
+        let paths = containment_paths(&graph, first, last);
+        assert_eq!(1, paths.len());
+        assert_eq!(100_000, paths[0].len());
+    }
+
+    #[test]
+    fn test_roots_and_leaves() {
+        let graph = parse_graph(RULES);
+
+        assert_eq!(
+            vec!["light red", "dotted black"],
+            colors(&graph, &roots(&graph))
+        );
+        assert_eq!(
+            vec!["faded blue", "dotted black"],
+            colors(&graph, &leaves(&graph))
+        );
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph = parse_graph(RULES);
+
+        assert_eq!(
+            vec![
+                "light red",
+                "dotted black",
+                "bright white",
+                "muted yellow",
+                "shiny gold",
+                "faded blue"
+            ],
+            colors(&graph, &topological_order(&graph).unwrap())
+        );
+
+        let graph = parse_graph(
+            "light red bags contain 1 bright white bag.
+bright white bags contain 1 light red bag.",
+        );
+        assert!(matches!(
+            topological_order(&graph),
+            Err(RuleError::Cycle(_))
+        ));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::bag_definition::parse_input;
+use crate::bag_graph::BagGraph;
+
+/// the graph of rules that are known to be valid
+pub(crate) fn parse_graph(input: &str) -> BagGraph {
+    BagGraph::new(&parse_input(input.lines().map(String::from).collect()).unwrap())
+}
+
+/// `len` rules, each bag containing one bag of the next rule
+pub(crate) fn chain(len: usize) -> String {
+    let mut rules = (1..len)
+        .map(|i| format!("shade{} blue bags contain 1 shade{} blue bag.\n", i - 1, i))
+        .collect::<String>();
+    rules.push_str(&format!(
+        "shade{} blue bags contain no other bags.",
+        len - 1
+    ));
+    rules
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use crate::bag_graph::{BagGraph, ColorId};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum RuleError {
+    /// the colors along the cycle, the first color is repeated at the end
+    Cycle(Vec<String>),
+    UnknownColor {
+        color: String,
+        referenced_by: String,
+    },
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Cycle(path) => write!(f, "bags contain each other: {}", path.join(" -> ")),
+            RuleError::UnknownColor {
+                color,
+                referenced_by,
+            } => write!(
+                f,
+                "no rule for {} bags, referenced by {} bags",
+                color, referenced_by
+            ),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum State {
+    New,
+    OnPath,
+    Done,
+}
+
+/// A path of bags containing each other, the first bag is repeated at the end.
+pub fn find_cycle(graph: &BagGraph) -> Option<Vec<ColorId>> {
+    let mut state = vec![State::New; graph.len()];
+
+    for start in graph.ids() {
+        if state[start] != State::New {
+            continue;
+        }
+
+        // depth first search without recursion, `next_edge` holds the index of
+        // the next inner bag to visit for every bag on the path
+        state[start] = State::OnPath;
+        let mut path = vec![start];
+        let mut next_edge = vec![0];
+
+        while let Some(&curr) = path.last() {
+            let edge = next_edge.last_mut().unwrap();
+
+            match graph.contains(curr).get(*edge) {
+                Some(&(inner, _count)) => {
+                    *edge += 1;
+                    match state[inner] {
+                        State::New => {
+                            state[inner] = State::OnPath;
+                            path.push(inner);
+                            next_edge.push(0);
+                        }
+                        State::OnPath => {
+                            let begin = path.iter().position(|id| *id == inner).unwrap();
+                            let mut cycle = path.split_off(begin);
+                            cycle.push(inner);
+                            return Some(cycle);
+                        }
+                        State::Done => {}
+                    }
+                }
+                None => {
+                    state[curr] = State::Done;
+                    path.pop();
+                    next_edge.pop();
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Checks that every referenced color has a rule and no bag contains itself.
+pub fn validate(graph: &BagGraph) -> Result<(), RuleError> {
+    if let Some(id) = graph.ids().find(|id| !graph.has_rule(*id)) {
+        // bags without rule are only created by being referenced
+        let (outer, _count) = graph.contained_by(id)[0];
+        return Err(RuleError::UnknownColor {
+            color: String::from(graph.color(id)),
+            referenced_by: String::from(graph.color(outer)),
+        });
+    }
+
+    if let Some(cycle) = find_cycle(graph) {
+        let cycle = cycle.iter().map(|id| String::from(graph.color(*id)));
+        return Err(RuleError::Cycle(cycle.collect()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse_graph;
+
+    #[test]
+    fn test_valid_rules() {
+        let graph = parse_graph(
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 muted yellow bag.
+muted yellow bags contain no other bags.",
+        );
+
+        assert_eq!(None, find_cycle(&graph));
+        assert_eq!(Ok(()), validate(&graph));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let graph = parse_graph(
+            "light red bags contain 1 bright white bag.
+bright white bags contain 1 muted yellow bag, 2 faded blue bags.
+faded blue bags contain no other bags.
+muted yellow bags contain 3 light red bags.",
+        );
+
+        assert_eq!(
+            Err(RuleError::Cycle(
+                ["light red", "bright white", "muted yellow", "light red"]
+                    .iter()
+                    .map(|c| String::from(*c))
+                    .collect()
+            )),
+            validate(&graph)
+        );
+
+        let graph = parse_graph("shiny gold bags contain 1 shiny gold bag.");
+        let err = validate(&graph).unwrap_err();
+        assert_eq!(
+            "bags contain each other: shiny gold -> shiny gold",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_unknown_color() {
+        let graph = parse_graph("light red bags contain 1 bright white bag.");
+
+        assert_eq!(
+            Err(RuleError::UnknownColor {
+                color: String::from("bright white"),
+                referenced_by: String::from("light red")
+            }),
+            validate(&graph)
+        );
+    }
+}
@@ -1,127 +1,96 @@
-use std::collections::HashMap;
-
-mod lib;
-
-#[derive(PartialEq, Eq, Hash)]
-enum Operation {
-    Nop,
-    Acc,
-    Jmp,
-}
-
-impl From<&str> for Operation {
-    fn from(s: &str) -> Self {
-        return match s {
-            "nop" => Operation::Nop,
-            "acc" => Operation::Acc,
-            "jmp" => Operation::Jmp,
-            _ => panic!(),
-        };
-    }
+use std::io;
+use std::{env, fs, process};
+
+use console::analysis::Analysis;
+use console::assembler::parse_program;
+use console::debugger::Debugger;
+use console::disassembler::{disassemble, Options};
+use console::instruction::Program;
+use console::machine::Machine;
+use console::repair::repair;
+use console::trace::trace;
+
+fn read_program(file: &str) -> Program {
+    let input = fs::read_to_string(file).unwrap_or_else(|e| {
+        eprintln!("{}: {}", file, e);
+        process::exit(1);
+    });
+
+    parse_program(&input).unwrap_or_else(|e| {
+        eprintln!("{}: {}", file, e);
+        process::exit(1);
+    })
 }
 
-#[derive(PartialEq, Eq, Hash)]
-struct Instruction {
-    operation: Operation,
-    argument: i32,
-}
-
-impl Instruction {}
-
-impl From<String> for Instruction {
-    fn from(s: String) -> Self {
-        let mut split = s.splitn(2, ' ');
-        let op = split.next().unwrap();
-        let op = Operation::from(op);
-
-        let arg = split.next().unwrap();
-        let arg = arg.parse::<i32>().unwrap();
-
-        Instruction {
-            operation: op,
-            argument: arg,
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let file = args.get(1).map_or("input.test", String::as_str);
+
+    match args.first().map(String::as_str) {
+        // usage: day08 trace [file]
+        Some("trace") => {
+            let mut machine = Machine::new(read_program(file));
+            let halt = trace(&mut machine, |entry| println!("{}", entry));
+            println!("{}", halt);
+            return;
         }
-    }
-}
-
-fn parse_input(input: Vec<String>) -> Vec<Instruction> {
-    input.into_iter().map(Instruction::from).collect()
-}
-
-fn get_acc_on_infinity_loop(instructions: &Vec<Instruction>, swap_index: usize) -> (i32, bool) {
-    let mut accumulator: i32 = 0;
-    let mut instruction_ptr: usize = 0;
-    let mut step_count = 1;
-
-    let mut executed_instructions: HashMap<usize, &Instruction> = HashMap::new();
-    let mut curr_instruction = instructions.get(instruction_ptr).unwrap();
-
-    while !executed_instructions.contains_key(&instruction_ptr) {
-        executed_instructions.insert(instruction_ptr, curr_instruction);
-        let mut curr_operation = &curr_instruction.operation;
-        if instruction_ptr == swap_index {
-            match curr_operation {
-                Operation::Nop => curr_operation = &Operation::Jmp,
-                Operation::Jmp => curr_operation = &Operation::Nop,
-                _ => panic!(),
+        // usage: day08 debug [file]
+        Some("debug") => {
+            let mut debugger = Debugger::new(Machine::new(read_program(file)));
+            if let Err(e) = debugger.run(io::stdin().lock(), io::stdout()) {
+                eprintln!("{}", e);
+                process::exit(1);
             }
+            return;
         }
-        match curr_operation {
-            Operation::Nop => instruction_ptr += 1,
-            Operation::Acc => {
-                accumulator += curr_instruction.argument;
-                instruction_ptr += 1;
-            }
-            Operation::Jmp => {
-                instruction_ptr = (instruction_ptr as i32 + curr_instruction.argument) as usize
+        // usage: day08 analyze <text|dot> [file]
+        Some("analyze") => {
+            let file = args.get(2).map_or("input.test", String::as_str);
+            let analysis = Analysis::new(&read_program(file));
+            match args.get(1).map(String::as_str) {
+                Some("text") => print!("{}", analysis),
+                Some("dot") => print!("{}", analysis.to_dot()),
+                _ => {
+                    eprintln!("usage: day08 analyze <text|dot> [file]");
+                    process::exit(1);
+                }
             }
+            return;
         }
-
-        step_count += 1;
-        let tmp = instructions.get(instruction_ptr);
-        if tmp.is_none() {
-            return (accumulator, true);
-        } else {
-            curr_instruction = tmp.unwrap()
+        // usage: day08 disasm [--lines] [--jumps] [--blocks] [file]
+        Some("disasm") => {
+            let mut options = Options::default();
+            let mut file = "input.test";
+            for arg in &args[1..] {
+                match arg.as_str() {
+                    "--lines" => options.line_numbers = true,
+                    "--jumps" => options.jump_targets = true,
+                    "--blocks" => options.blocks = true,
+                    _ if arg.starts_with("--") => {
+                        eprintln!("usage: day08 disasm [--lines] [--jumps] [--blocks] [file]");
+                        process::exit(1);
+                    }
+                    _ => file = arg,
+                }
+            }
+            print!("{}", disassemble(&read_program(file), options));
+            return;
         }
+        _ => {}
     }
 
-    (accumulator, false)
-}
-
-fn find_next_instruction_index_to_change(
-    instructions: &Vec<Instruction>,
-    curr_index: usize,
-) -> Option<usize> {
-    for i in curr_index..instructions.len() {
-        match instructions.get(i).unwrap().operation {
-            Operation::Nop | Operation::Jmp => return Some(i),
-            _ => continue,
+    let program = read_program("input.test");
+    match repair(&program) {
+        Ok(repair) => {
+            println!(
+                "CHANGED: instruction {} to {}",
+                repair.index, repair.instruction
+            );
+            println!("RESULT: {}", repair.acc);
         }
-    }
-
-    None
-}
-
-fn get_acc_for_correct_instruction_set(instructions: Vec<Instruction>) -> Option<i32> {
-    let mut curr_index: Option<usize> = find_next_instruction_index_to_change(&instructions, 0);
-
-    while curr_index.is_some() {
-        let (acc, has_finished) = get_acc_on_infinity_loop(&instructions, curr_index.unwrap());
-        if has_finished {
-            return Some(acc);
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
         }
-        curr_index = find_next_instruction_index_to_change(&instructions, curr_index.unwrap() + 1);
     }
-
-    None
-}
-
-fn main() {
-    let input: Vec<String> = lib::get_input("input.test");
-    let instructions = parse_input(input);
-
-    let result = get_acc_for_correct_instruction_set(instructions);
-
-    println!("RESULT: {}", result.unwrap());
 }
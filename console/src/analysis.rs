@@ -0,0 +1,251 @@
+use std::fmt::{self, Write};
+
+use crate::cfg::{successors, ControlFlowGraph};
+use crate::instruction::Instruction;
+use crate::repair::terminating_swaps;
+
+/// Swapping `jmp` and `nop` at `index` makes the program terminate.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Fix {
+    pub index: usize,
+    /// the instruction replacing the one at `index`
+    pub instruction: Instruction,
+}
+
+/// What can be said about a program without running it.
+///
+/// With conditional jumps both branches are assumed to be possible, so
+/// unreachable instructions and instructions that never terminate are
+/// still certain, but fixes are only computed for programs without them.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub program: Vec<Instruction>,
+    pub cfg: ControlFlowGraph,
+    /// instructions the program can't get to from ip 0
+    pub unreachable: Vec<usize>,
+    /// instructions with no way to the end of the program
+    pub never_terminates: Vec<usize>,
+    /// every single swap making the program terminate, empty if it already
+    /// does, `None` for programs with conditional jumps
+    pub fixes: Option<Vec<Fix>>,
+    reachable: Vec<bool>,
+    reaches_end: Vec<bool>,
+}
+
+/// The instructions executed from ip 0 on until the program ends or loops,
+/// only for programs where every instruction has a single successor.
+fn path(cfg: &ControlFlowGraph) -> Vec<usize> {
+    let mut on_path = vec![false; cfg.end() + 1];
+    let mut path = Vec::new();
+    let mut ip = 0;
+
+    while ip != cfg.end() && !on_path[ip] {
+        on_path[ip] = true;
+        path.push(ip);
+        match cfg.successors(ip).first() {
+            Some(next) => ip = *next,
+            None => break,
+        }
+    }
+
+    path
+}
+
+impl Analysis {
+    pub fn new(program: &[Instruction]) -> Analysis {
+        let cfg = ControlFlowGraph::new(program);
+        let reachable = cfg.reachable();
+        let reaches_end = cfg.reaches_end();
+
+        let unreachable = (0..program.len()).filter(|ip| !reachable[*ip]).collect();
+        let never_terminates = (0..program.len()).filter(|ip| !reaches_end[*ip]).collect();
+
+        let conditional = program
+            .iter()
+            .any(|instruction| instruction.operation.opcode().conditional);
+        let fixes = if conditional {
+            None
+        } else if reaches_end[0] {
+            // a program terminating on its own doesn't need a fix
+            Some(Vec::new())
+        } else {
+            let fixes = terminating_swaps(program, path(&cfg), &reaches_end)
+                .map(|(index, instruction)| Fix { index, instruction })
+                .collect();
+            Some(fixes)
+        };
+
+        Analysis {
+            program: program.to_vec(),
+            cfg,
+            unreachable,
+            never_terminates,
+            fixes,
+            reachable,
+            reaches_end,
+        }
+    }
+
+    /// The control flow graph in Graphviz DOT.
+    ///
+    /// Unreachable instructions are dashed, instructions that never terminate
+    /// are red and fixes are dotted edges to the new successor.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph program {\n");
+        let end = self.cfg.end();
+
+        // writing into a String never fails
+        for (ip, instruction) in self.program.iter().enumerate() {
+            let mut style = Vec::new();
+            if !self.reachable[ip] {
+                style.push("style=dashed");
+            }
+            if !self.reaches_end[ip] {
+                style.push("color=red");
+            }
+            let style = style.iter().map(|s| format!(", {}", s)).collect::<String>();
+            writeln!(
+                dot,
+                "    i{} [label=\"{}: {}\"{}];",
+                ip, ip, instruction, style
+            )
+            .unwrap();
+        }
+        writeln!(dot, "    i{} [label=\"end\", shape=doublecircle];", end).unwrap();
+
+        for ip in 0..end {
+            for next in self.cfg.successors(ip) {
+                writeln!(dot, "    i{} -> i{};", ip, next).unwrap();
+            }
+        }
+        for fix in self.fixes.iter().flatten() {
+            let next = successors(self.program.len(), fix.index, &fix.instruction);
+            if let Some(next) = next.first() {
+                writeln!(
+                    dot,
+                    "    i{} -> i{} [style=dotted, label=\"{}\"];",
+                    fix.index, next, fix.instruction
+                )
+                .unwrap();
+            }
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+fn write_ips(f: &mut fmt::Formatter<'_>, title: &str, ips: &[usize]) -> fmt::Result {
+    let ips = ips.iter().map(|ip| ip.to_string()).collect::<Vec<_>>();
+    writeln!(f, "{} ({}): {}", title, ips.len(), ips.join(", "))
+}
+
+impl fmt::Display for Analysis {
+    /// the control flow graph with one line per instruction, then the findings
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ip, instruction) in self.program.iter().enumerate() {
+            let next = self
+                .cfg
+                .successors(ip)
+                .iter()
+                .map(|next| {
+                    if *next == self.cfg.end() {
+                        String::from("end")
+                    } else {
+                        next.to_string()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let next = if next.is_empty() {
+                String::from("out of bounds")
+            } else {
+                next.join(", ")
+            };
+            writeln!(f, "{:>5}: {:<10} -> {}", ip, instruction, next)?;
+        }
+        writeln!(f)?;
+
+        write_ips(f, "unreachable", &self.unreachable)?;
+        write_ips(f, "never terminates", &self.never_terminates)?;
+        match &self.fixes {
+            Some(fixes) => {
+                let fixes = fixes
+                    .iter()
+                    .map(|fix| format!("{} to {}", fix.index, fix.instruction))
+                    .collect::<Vec<_>>();
+                writeln!(f, "fixes ({}): {}", fixes.len(), fixes.join(", "))
+            }
+            None => writeln!(f, "fixes: unknown because of conditional jumps"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::parse_program;
+    use crate::instruction::Operation;
+    use crate::test_util::EXAMPLE;
+
+    #[test]
+    fn test_analysis() {
+        let analysis = Analysis::new(&parse_program(EXAMPLE).unwrap());
+
+        assert_eq!(vec![5, 8], analysis.unreachable);
+        assert_eq!(vec![0, 1, 2, 3, 4, 5, 6, 7], analysis.never_terminates);
+        assert_eq!(
+            Some(vec![Fix {
+                index: 7,
+                instruction: Instruction::new(Operation::Nop, -4)
+            }]),
+            analysis.fixes
+        );
+    }
+
+    #[test]
+    fn test_several_fixes() {
+        let analysis = Analysis::new(&parse_program("nop +2\nnop +3\njmp +0\nacc +1").unwrap());
+        let fixes = analysis.fixes.unwrap();
+
+        assert_eq!(
+            vec![1, 2],
+            fixes.iter().map(|fix| fix.index).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![3], analysis.unreachable);
+
+        let analysis = Analysis::new(&parse_program("acc +1\nnop +0").unwrap());
+        assert_eq!(Some(Vec::new()), analysis.fixes);
+        assert!(analysis.never_terminates.is_empty());
+
+        let analysis = Analysis::new(&parse_program("jz +0").unwrap());
+        assert_eq!(None, analysis.fixes);
+        assert!(analysis.never_terminates.is_empty());
+    }
+
+    #[test]
+    fn test_output() {
+        let analysis =
+            Analysis::new(&parse_program("nop +0\njmp -1\nout\njmp +2\njmp -9").unwrap());
+
+        assert_eq!(
+            "    0: nop +0     -> 1
+    1: jmp -1     -> 0
+    2: out        -> 3
+    3: jmp +2     -> end
+    4: jmp -9     -> out of bounds
+
+unreachable (3): 2, 3, 4
+never terminates (3): 0, 1, 4
+fixes (1): 1 to nop -1
+",
+            analysis.to_string()
+        );
+
+        let dot = analysis.to_dot();
+        assert!(dot.contains("    i2 [label=\"2: out\", style=dashed];\n"));
+        assert!(dot.contains("    i4 [label=\"4: jmp -9\", style=dashed, color=red];\n"));
+        assert!(dot.contains("    i5 [label=\"end\", shape=doublecircle];\n"));
+        assert!(dot.contains("    i1 -> i0;\n"));
+        assert!(dot.contains("    i1 -> i2 [style=dotted, label=\"nop -1\"];\n"));
+    }
+}
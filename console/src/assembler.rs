@@ -0,0 +1,327 @@
+use std::fmt;
+
+use crate::instruction::{ArgumentKind, Instruction, Operation, Program, REGISTERS};
+
+/// Line and column numbers start at 1.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AsmError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: AsmErrorKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum AsmErrorKind {
+    MissingOperation,
+    UnknownOperation(String),
+    MissingArgument,
+    InvalidArgument(String),
+    InvalidRegister(String),
+    UnexpectedInput(String),
+    /// the address in front of the instruction isn't its index
+    WrongAddress {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            AsmErrorKind::MissingOperation => write!(f, "expected an operation"),
+            AsmErrorKind::UnknownOperation(op) => write!(f, "unknown operation '{}'", op),
+            AsmErrorKind::MissingArgument => write!(f, "expected an argument"),
+            AsmErrorKind::InvalidArgument(arg) => {
+                write!(
+                    f,
+                    "'{}' is not a valid argument, expected a 32 bit number",
+                    arg
+                )
+            }
+            AsmErrorKind::InvalidRegister(register) => write!(
+                f,
+                "'{}' is not a register, expected r0 to r{}",
+                register,
+                REGISTERS - 1
+            ),
+            AsmErrorKind::UnexpectedInput(input) => {
+                write!(f, "unexpected '{}' after the instruction", input)
+            }
+            AsmErrorKind::WrongAddress { expected, found } => {
+                write!(f, "address {} should be {}", found, expected)
+            }
+        }
+    }
+}
+
+/// Comments start with `;` or `#` and run until the end of the line.
+fn strip_comment(line: &str) -> &str {
+    match line.find([';', '#']) {
+        Some(start) => &line[..start],
+        None => line,
+    }
+}
+
+/// the whitespace separated words of `line` with their column
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (i, c) in line.char_indices() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(begin)) => {
+                words.push((begin, &line[begin..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        words.push((begin, &line[begin..]));
+    }
+
+    words
+        .into_iter()
+        .map(|(i, word)| (line[..i].chars().count() + 1, word))
+        .collect()
+}
+
+fn parse_argument(kind: ArgumentKind, arg: &str) -> Option<i32> {
+    match kind {
+        // an optional `+` is accepted by `i32::from_str`
+        ArgumentKind::Number | ArgumentKind::Offset => arg.parse().ok(),
+        ArgumentKind::Register => arg
+            .strip_prefix('r')
+            .filter(|index| index.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|index| index.parse::<usize>().ok())
+            .filter(|index| *index < REGISTERS)
+            .map(|index| index as i32),
+        ArgumentKind::None => Some(0),
+    }
+}
+
+/// the address of a word like `17:`
+fn parse_address(word: &str) -> Option<usize> {
+    word.strip_suffix(':')
+        .filter(|address| !address.is_empty() && address.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|address| address.parse().ok())
+}
+
+/// Parses one line of a program, `None` for blank and comment lines.
+///
+/// The instruction may start with its address like `17: jmp +4`, which has
+/// to be `ip`, the index the instruction gets in the program.
+pub(crate) fn parse_instruction(
+    line: &str,
+    line_nr: usize,
+    ip: usize,
+) -> Result<Option<Instruction>, AsmError> {
+    let code = strip_comment(line);
+    let error = |column, kind| AsmError {
+        line: line_nr,
+        column,
+        kind,
+    };
+
+    let mut words = words(code);
+    if let Some((column, address)) = words
+        .first()
+        .and_then(|(column, word)| parse_address(word).map(|address| (*column, address)))
+    {
+        if address != ip {
+            return Err(error(
+                column,
+                AsmErrorKind::WrongAddress {
+                    expected: ip,
+                    found: address,
+                },
+            ));
+        }
+        words.remove(0);
+        if words.is_empty() {
+            return Err(error(column, AsmErrorKind::MissingOperation));
+        }
+    }
+    let (op_column, op) = match words.first() {
+        Some(word) => *word,
+        None => return Ok(None),
+    };
+    let operation = op
+        .parse::<Operation>()
+        .map_err(|_| error(op_column, AsmErrorKind::UnknownOperation(String::from(op))))?;
+
+    let kind = operation.opcode().argument;
+    let mut rest = words[1..].iter();
+
+    let argument = if kind == ArgumentKind::None {
+        0
+    } else {
+        let (arg_column, arg) = match rest.next() {
+            Some(word) => *word,
+            None => {
+                let end = op_column + op.chars().count();
+                return Err(error(end, AsmErrorKind::MissingArgument));
+            }
+        };
+        parse_argument(kind, arg).ok_or_else(|| {
+            let arg = String::from(arg);
+            match kind {
+                ArgumentKind::Register => error(arg_column, AsmErrorKind::InvalidRegister(arg)),
+                _ => error(arg_column, AsmErrorKind::InvalidArgument(arg)),
+            }
+        })?
+    };
+
+    if let Some((column, rest)) = rest.next() {
+        return Err(error(
+            *column,
+            AsmErrorKind::UnexpectedInput(String::from(*rest)),
+        ));
+    }
+
+    Ok(Some(Instruction::new(operation, argument)))
+}
+
+/// Parses a program with one instruction per line, blank lines and comments
+/// are skipped. Instructions may start with their address.
+pub fn parse_program(input: &str) -> Result<Program, AsmError> {
+    let mut program = Program::new();
+
+    for (i, line) in input.lines().enumerate() {
+        if let Some(instruction) = parse_instruction(line, i + 1, program.len())? {
+            program.push(instruction);
+        }
+    }
+
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_program() {
+        let program = parse_program(
+            "# boot code
+nop +0
+  acc   1   ; no sign
+
+jmp\t-3
+acc -99;comment
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Instruction::new(Operation::Nop, 0),
+                Instruction::new(Operation::Acc, 1),
+                Instruction::new(Operation::Jmp, -3),
+                Instruction::new(Operation::Acc, -99),
+            ],
+            program
+        );
+        assert_eq!(Ok(Instruction::new(Operation::Jmp, 4)), "jmp +4".parse());
+        assert_eq!(Ok(Operation::Nop), "nop".parse());
+    }
+
+    #[test]
+    fn test_addresses() {
+        let program = parse_program(
+            "0: nop +0
+; 1: jmp -1
+
+ 1:  acc +1
+2: out",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Instruction::new(Operation::Nop, 0),
+                Instruction::new(Operation::Acc, 1),
+                Instruction::new(Operation::Out, 0),
+            ],
+            program
+        );
+        assert_eq!(
+            "2:1: address 2 should be 1",
+            parse_program(
+                "0: nop +0
+2: nop +0"
+            )
+            .unwrap_err()
+            .to_string()
+        );
+        assert_eq!(
+            "1:1: expected an operation",
+            parse_program("0: ; nop +0").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "1:1: unknown operation 'loop:'",
+            parse_program("loop: nop +0").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_extended_operations() {
+        let program = parse_program("in\nst r3\nmul -2\njz +2\nld  r0\njnz -4\nout").unwrap();
+
+        assert_eq!(
+            vec![
+                Instruction::new(Operation::In, 0),
+                Instruction::new(Operation::St, 3),
+                Instruction::new(Operation::Mul, -2),
+                Instruction::new(Operation::Jz, 2),
+                Instruction::new(Operation::Ld, 0),
+                Instruction::new(Operation::Jnz, -4),
+                Instruction::new(Operation::Out, 0),
+            ],
+            program
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |input| parse_program(input).unwrap_err().to_string();
+
+        assert_eq!(
+            AsmError {
+                line: 2,
+                column: 3,
+                kind: AsmErrorKind::UnknownOperation(String::from("hlt"))
+            },
+            parse_program("nop +0\n  hlt +2").unwrap_err()
+        );
+        assert_eq!("1:4: expected an argument", error("acc"));
+        assert_eq!("1:4: expected an argument", error("acc ; +1"));
+        assert_eq!(
+            "3:5: '++1' is not a valid argument, expected a 32 bit number",
+            error("\n\nacc ++1")
+        );
+        assert_eq!(
+            "1:5: '4294967296' is not a valid argument, expected a 32 bit number",
+            error("jmp 4294967296")
+        );
+        assert_eq!(
+            "1:8: unexpected 'x' after the instruction",
+            error("jmp +4 x")
+        );
+        assert_eq!(
+            "1:5: unexpected '+1' after the instruction",
+            error("out +1")
+        );
+        assert_eq!(
+            "1:4: 'r8' is not a register, expected r0 to r7",
+            error("st r8")
+        );
+        assert_eq!("1:3: expected an argument", error("ld"));
+        assert_eq!(
+            "1:1: expected an operation",
+            "  ".parse::<Instruction>().unwrap_err().to_string()
+        );
+    }
+}
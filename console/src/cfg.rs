@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use crate::instruction::{ArgumentKind, Instruction};
+
+/// Where the program may continue after `instruction` at `ip`.
+///
+/// Conditional jumps have both possible successors, jumps out of bounds
+/// have none. `program_len`, right after the last instruction, is the end.
+pub fn successors(program_len: usize, ip: usize, instruction: &Instruction) -> Vec<usize> {
+    let opcode = instruction.operation.opcode();
+    let next = ip + 1;
+
+    if opcode.argument != ArgumentKind::Offset {
+        return vec![next];
+    }
+
+    let target = ip as i64 + i64::from(instruction.argument);
+    let target = if target < 0 || target > program_len as i64 {
+        None
+    } else {
+        Some(target as usize)
+    };
+
+    match (opcode.conditional, target) {
+        (true, Some(target)) if target != next => vec![next, target],
+        (true, _) => vec![next],
+        (false, Some(target)) => vec![target],
+        (false, None) => Vec::new(),
+    }
+}
+
+/// The control flow graph of a program with edges in both directions.
+///
+/// There is a node for every instruction and one more for the end of the
+/// program.
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl ControlFlowGraph {
+    pub fn new(program: &[Instruction]) -> ControlFlowGraph {
+        let mut successors = Vec::with_capacity(program.len() + 1);
+        let mut predecessors = vec![Vec::new(); program.len() + 1];
+
+        for (ip, instruction) in program.iter().enumerate() {
+            let next = self::successors(program.len(), ip, instruction);
+            for &successor in &next {
+                predecessors[successor].push(ip);
+            }
+            successors.push(next);
+        }
+        // the end has no successors
+        successors.push(Vec::new());
+
+        ControlFlowGraph {
+            successors,
+            predecessors,
+        }
+    }
+
+    /// the node for the end of the program
+    pub fn end(&self) -> usize {
+        self.successors.len() - 1
+    }
+
+    pub fn successors(&self, ip: usize) -> &[usize] {
+        &self.successors[ip]
+    }
+
+    pub fn predecessors(&self, ip: usize) -> &[usize] {
+        &self.predecessors[ip]
+    }
+
+    /// whether the program can get to each node when starting at ip 0
+    pub fn reachable(&self) -> Vec<bool> {
+        self.search(0, |ip| self.successors(ip))
+    }
+
+    /// whether each node can get to the end of the program
+    pub fn reaches_end(&self) -> Vec<bool> {
+        self.search(self.end(), |ip| self.predecessors(ip))
+    }
+
+    fn search<'a, F>(&'a self, start: usize, edges: F) -> Vec<bool>
+    where
+        F: Fn(usize) -> &'a [usize],
+    {
+        let mut found = vec![false; self.successors.len()];
+        found[start] = true;
+        let mut queue = VecDeque::from(vec![start]);
+
+        while let Some(ip) = queue.pop_front() {
+            for &next in edges(ip) {
+                if !found[next] {
+                    found[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::parse_program;
+
+    #[test]
+    fn test_successors() {
+        let successors = |line: &str, ip| successors(4, ip, &line.parse().unwrap());
+
+        assert_eq!(vec![2], successors("acc +7", 1));
+        assert_eq!(vec![4], successors("jmp +3", 1));
+        assert_eq!(Vec::<usize>::new(), successors("jmp -2", 1));
+        assert_eq!(vec![2, 0], successors("jnz -1", 1));
+        assert_eq!(vec![2], successors("jz +1", 1));
+        assert_eq!(vec![2], successors("jz +9", 1));
+    }
+
+    #[test]
+    fn test_graph() {
+        let program = parse_program("nop +0\njmp +2\nacc +1\njmp -3").unwrap();
+        let cfg = ControlFlowGraph::new(&program);
+
+        assert_eq!(4, cfg.end());
+        assert_eq!(&[3], cfg.successors(1));
+        assert_eq!(&[1, 2], cfg.predecessors(3));
+        assert_eq!(&[3], cfg.predecessors(0));
+        assert_eq!(vec![true, true, false, true, false], cfg.reachable());
+        assert_eq!(vec![false, false, false, false, true], cfg.reaches_end());
+    }
+}
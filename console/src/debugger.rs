@@ -0,0 +1,190 @@
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::machine::Machine;
+use crate::trace::trace_step;
+
+const HELP: &str = "commands: step, continue, break <ip>, print acc, print ip, set ip <ip>, quit";
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Command {
+    /// execute a single instruction
+    Step,
+    /// execute instructions until the machine halts or hits a breakpoint
+    Continue,
+    Break(usize),
+    PrintAcc,
+    PrintIp,
+    SetIp(usize),
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let ip = |ip: &str| ip.parse().map_err(|_| ());
+
+        let command = match words[..] {
+            ["step"] => Command::Step,
+            ["continue"] => Command::Continue,
+            ["break", at] => Command::Break(ip(at)?),
+            ["print", "acc"] => Command::PrintAcc,
+            ["print", "ip"] => Command::PrintIp,
+            ["set", "ip", to] => Command::SetIp(ip(to)?),
+            ["quit"] => Command::Quit,
+            _ => return Err(()),
+        };
+
+        Ok(command)
+    }
+}
+
+/// An interactive debugger around a `Machine`, every executed instruction
+/// is traced.
+pub struct Debugger {
+    pub machine: Machine,
+    breakpoints: BTreeSet<usize>,
+}
+
+impl Debugger {
+    pub fn new(machine: Machine) -> Debugger {
+        Debugger {
+            machine,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Executes `command` and writes its output, `false` after `Quit`.
+    pub fn execute<W: Write>(&mut self, command: Command, out: &mut W) -> io::Result<bool> {
+        match command {
+            Command::Step => match trace_step(&mut self.machine) {
+                Ok(entry) => writeln!(out, "{}", entry)?,
+                Err(halt) => writeln!(out, "{}", halt)?,
+            },
+            Command::Continue => loop {
+                match trace_step(&mut self.machine) {
+                    Ok(entry) => writeln!(out, "{}", entry)?,
+                    Err(halt) => {
+                        writeln!(out, "{}", halt)?;
+                        break;
+                    }
+                }
+                if self.breakpoints.contains(&self.machine.ip) {
+                    writeln!(out, "breakpoint at ip {}", self.machine.ip)?;
+                    break;
+                }
+            },
+            Command::Break(ip) => {
+                self.breakpoints.insert(ip);
+            }
+            Command::PrintAcc => writeln!(out, "{}", self.machine.acc)?,
+            Command::PrintIp => writeln!(out, "{}", self.machine.ip)?,
+            Command::SetIp(ip) => self.machine.jump_to(ip),
+            Command::Quit => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Reads commands from `input` until `quit` or the end of the input.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut out: W) -> io::Result<()> {
+        writeln!(out, "{}", HELP)?;
+        write!(out, "(dbg) ")?;
+        out.flush()?;
+
+        for line in input.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                match line.parse() {
+                    Ok(command) => {
+                        if !self.execute(command, &mut out)? {
+                            return Ok(());
+                        }
+                    }
+                    Err(()) => writeln!(out, "{}", HELP)?,
+                }
+            }
+
+            write!(out, "(dbg) ")?;
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::parse_program;
+
+    fn debug(commands: &str) -> String {
+        let program = parse_program("nop +0\nacc +1\njmp +2\nacc +5\nacc +2").unwrap();
+        let mut debugger = Debugger::new(Machine::new(program));
+        let mut out = Vec::new();
+
+        debugger.run(commands.as_bytes(), &mut out).unwrap();
+
+        String::from_utf8(out).unwrap().replace("(dbg) ", "")
+    }
+
+    #[test]
+    fn test_command() {
+        assert_eq!(Ok(Command::Break(12)), "break 12".parse());
+        assert_eq!(Ok(Command::SetIp(3)), " set  ip 3 ".parse());
+        assert_eq!(Err(()), "break".parse::<Command>());
+        assert_eq!(Err(()), "set ip -1".parse::<Command>());
+    }
+
+    #[test]
+    fn test_debugger() {
+        let out =
+            debug("step\nbreak 4\ncontinue\nprint acc\nprint ip\nset ip 3\ncontinue\ncontinue\nquit\nstep\n");
+
+        assert_eq!(
+            format!(
+                "{}
+    0: nop +0  acc 0 -> 0
+    1: acc +1  acc 0 -> 1
+    2: jmp +2  acc 1 -> 1
+breakpoint at ip 4
+1
+4
+    3: acc +5  acc 1 -> 6
+breakpoint at ip 4
+    4: acc +2  acc 6 -> 8
+terminated
+",
+                HELP
+            ),
+            out
+        );
+    }
+
+    #[test]
+    fn test_set_ip_backwards() {
+        let out = debug("step\nstep\nset ip 0\nstep\nstep\nstep\n");
+
+        assert_eq!(
+            format!(
+                "{}
+    0: nop +0  acc 0 -> 0
+    1: acc +1  acc 0 -> 1
+    0: nop +0  acc 1 -> 1
+    1: acc +1  acc 1 -> 2
+    2: jmp +2  acc 2 -> 2
+",
+                HELP
+            ),
+            out
+        );
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        assert_eq!(format!("{}\n{}\n", HELP, HELP), debug("jump 3\n"));
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::Write;
+
+use crate::cfg::successors;
+use crate::instruction::{ArgumentKind, Instruction};
+
+/// What to print next to the instructions, nothing by default.
+///
+/// Everything is printed in a way the assembler accepts, so the output
+/// always parses back to the same program.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct Options {
+    /// the address in front of every instruction, like `17: jmp +4`
+    pub line_numbers: bool,
+    /// a comment with the target of every jump, like `jmp +4  ; -> 21`
+    pub jump_targets: bool,
+    /// a blank line in front of every basic block but the first
+    pub blocks: bool,
+}
+
+/// Where a jump at `ip` goes, as it's written in the comment.
+fn target_text(program_len: usize, ip: usize, instruction: &Instruction) -> String {
+    let target = ip as i64 + i64::from(instruction.argument);
+
+    if target < 0 || target > program_len as i64 {
+        String::from("out of bounds")
+    } else if target == program_len as i64 {
+        String::from("end")
+    } else {
+        target.to_string()
+    }
+}
+
+/// Whether each instruction starts a basic block: the first one, every
+/// target of a jump and every instruction after a jump.
+fn leaders(program: &[Instruction]) -> Vec<bool> {
+    let mut leaders = vec![false; program.len() + 1];
+    leaders[0] = true;
+
+    for (ip, instruction) in program.iter().enumerate() {
+        if instruction.operation.opcode().argument == ArgumentKind::Offset {
+            leaders[ip + 1] = true;
+            for next in successors(program.len(), ip, instruction) {
+                leaders[next] = true;
+            }
+        }
+    }
+
+    leaders.truncate(program.len());
+    leaders
+}
+
+/// Prints a program with one instruction per line.
+///
+/// With the default options this is the canonical text of the program, the
+/// input of the puzzle is already in it.
+pub fn disassemble(program: &[Instruction], options: Options) -> String {
+    let mut text = String::new();
+    let width = program.len().saturating_sub(1).to_string().len();
+    let leaders = leaders(program);
+
+    // writing into a String never fails
+    for (ip, instruction) in program.iter().enumerate() {
+        if options.blocks && ip > 0 && leaders[ip] {
+            text.push('\n');
+        }
+        if options.line_numbers {
+            write!(text, "{:>width$}: ", ip, width = width).unwrap();
+        }
+        write!(text, "{}", instruction).unwrap();
+        if options.jump_targets && instruction.operation.opcode().argument == ArgumentKind::Offset {
+            write!(
+                text,
+                "  ; -> {}",
+                target_text(program.len(), ip, instruction)
+            )
+            .unwrap();
+        }
+        text.push('\n');
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::parse_program;
+    use crate::test_util::EXAMPLE;
+
+    const ALL: Options = Options {
+        line_numbers: true,
+        jump_targets: true,
+        blocks: true,
+    };
+
+    #[test]
+    fn test_canonical() {
+        let program = parse_program(EXAMPLE).unwrap();
+        assert_eq!(EXAMPLE, disassemble(&program, Options::default()));
+
+        let program = parse_program("  acc 1 ; one\n\nst   r2\nin\n").unwrap();
+        assert_eq!(
+            "acc +1\nst r2\nin\n",
+            disassemble(&program, Options::default())
+        );
+    }
+
+    #[test]
+    fn test_annotations() {
+        let program = parse_program(EXAMPLE).unwrap();
+
+        assert_eq!(
+            "0: nop +0
+
+1: acc +1
+2: jmp +4  ; -> 6
+
+3: acc +3
+4: jmp -3  ; -> 1
+
+5: acc -99
+
+6: acc +1
+7: jmp -4  ; -> 3
+
+8: acc +6
+",
+            disassemble(&program, ALL)
+        );
+
+        let program = parse_program("jz +2\nout\njnz -5\njmp +0").unwrap();
+        let options = Options {
+            jump_targets: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            "jz +2  ; -> 2\nout\njnz -5  ; -> out of bounds\njmp +0  ; -> 3\n",
+            disassemble(&program, options)
+        );
+        assert_eq!(
+            "jz +2\n\nout\n\njnz -5\n\njmp +0\n",
+            disassemble(
+                &program,
+                Options {
+                    blocks: true,
+                    ..Options::default()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let program =
+            parse_program(&format!("{}jz +2\nmul -3\nld r7\njnz +1\nout", EXAMPLE)).unwrap();
+
+        for line_numbers in [false, true] {
+            for jump_targets in [false, true] {
+                for blocks in [false, true] {
+                    let options = Options {
+                        line_numbers,
+                        jump_targets,
+                        blocks,
+                    };
+                    let text = disassemble(&program, options);
+                    assert_eq!(Ok(program.clone()), parse_program(&text));
+                    assert_eq!(text, disassemble(&parse_program(&text).unwrap(), options));
+                }
+            }
+        }
+        assert_eq!("", disassemble(&[], ALL));
+    }
+}
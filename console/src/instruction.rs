@@ -0,0 +1,205 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::assembler::{parse_instruction, AsmError, AsmErrorKind};
+
+/// Number of general purpose registers `r0` to `r7`, next to the accumulator.
+pub const REGISTERS: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Operation {
+    Nop,
+    Acc,
+    Jmp,
+    Jz,
+    Jnz,
+    Mul,
+    Out,
+    In,
+    Ld,
+    St,
+}
+
+/// What the argument of an operation means.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ArgumentKind {
+    /// a signed number
+    Number,
+    /// a signed number of instructions to jump
+    Offset,
+    /// the index of a register, written as `r0` to `r7`
+    Register,
+    /// no argument, it's always 0
+    None,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Opcode {
+    pub operation: Operation,
+    pub mnemonic: &'static str,
+    pub argument: ArgumentKind,
+    /// whether the next instruction depends on the state of the machine
+    pub conditional: bool,
+}
+
+const fn opcode(
+    operation: Operation,
+    mnemonic: &'static str,
+    argument: ArgumentKind,
+    conditional: bool,
+) -> Opcode {
+    Opcode {
+        operation,
+        mnemonic,
+        argument,
+        conditional,
+    }
+}
+
+/// Every operation of the console.
+///
+/// A new operation needs an entry here and its effect in `Machine::step`,
+/// parsing and printing follow from the table.
+pub const OPCODES: [Opcode; 10] = [
+    // does nothing
+    opcode(Operation::Nop, "nop", ArgumentKind::Number, false),
+    // adds the argument to the accumulator
+    opcode(Operation::Acc, "acc", ArgumentKind::Number, false),
+    // jumps relative to itself
+    opcode(Operation::Jmp, "jmp", ArgumentKind::Offset, false),
+    // jumps if the accumulator is zero
+    opcode(Operation::Jz, "jz", ArgumentKind::Offset, true),
+    // jumps if the accumulator isn't zero
+    opcode(Operation::Jnz, "jnz", ArgumentKind::Offset, true),
+    // multiplies the accumulator with the argument
+    opcode(Operation::Mul, "mul", ArgumentKind::Number, false),
+    // appends the accumulator to the output
+    opcode(Operation::Out, "out", ArgumentKind::None, false),
+    // takes the next value of the input into the accumulator
+    opcode(Operation::In, "in", ArgumentKind::None, false),
+    // loads a register into the accumulator
+    opcode(Operation::Ld, "ld", ArgumentKind::Register, false),
+    // stores the accumulator into a register
+    opcode(Operation::St, "st", ArgumentKind::Register, false),
+];
+
+impl FromStr for Operation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OPCODES
+            .iter()
+            .find(|opcode| opcode.mnemonic == s)
+            .map(|opcode| opcode.operation)
+            .ok_or(())
+    }
+}
+
+impl Operation {
+    pub fn opcode(&self) -> &'static Opcode {
+        // unwrap is acceptable because every operation is in the table
+        OPCODES
+            .iter()
+            .find(|opcode| opcode.operation == *self)
+            .unwrap()
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        self.opcode().mnemonic
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.mnemonic())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Instruction {
+    pub operation: Operation,
+    /// meaning depends on the `ArgumentKind` of the operation
+    pub argument: i32,
+}
+
+impl Instruction {
+    pub fn new(operation: Operation, argument: i32) -> Instruction {
+        Instruction {
+            operation,
+            argument,
+        }
+    }
+
+    /// The argument like it's written in a program, `None` for operations
+    /// without argument.
+    pub fn argument_text(&self) -> Option<String> {
+        match self.operation.opcode().argument {
+            ArgumentKind::Number | ArgumentKind::Offset => Some(format!("{:+}", self.argument)),
+            ArgumentKind::Register => Some(format!("r{}", self.argument)),
+            ArgumentKind::None => None,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// the canonical text of the instruction, like `jmp +4` or `out`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.argument_text() {
+            Some(argument) => f.pad(&format!("{} {}", self.operation, argument)),
+            None => f.pad(self.operation.mnemonic()),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = AsmError;
+
+    /// A single instruction at address 0, errors are reported on line 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_instruction(s, 1, 0)?.ok_or(AsmError {
+            line: 1,
+            column: 1,
+            kind: AsmErrorKind::MissingOperation,
+        })
+    }
+}
+
+pub type Program = Vec<Instruction>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opcode_table() {
+        for (i, opcode) in OPCODES.iter().enumerate() {
+            assert_eq!(Ok(opcode.operation), opcode.mnemonic.parse());
+            assert_eq!(opcode, opcode.operation.opcode());
+            assert!(OPCODES[..i].iter().all(|o| o.operation != opcode.operation));
+        }
+        assert_eq!(Err(()), "hlt".parse::<Operation>());
+    }
+
+    #[test]
+    fn test_argument_text() {
+        let text = |operation, argument| Instruction::new(operation, argument).argument_text();
+
+        assert_eq!(Some(String::from("+0")), text(Operation::Nop, 0));
+        assert_eq!(Some(String::from("-3")), text(Operation::Jz, -3));
+        assert_eq!(Some(String::from("r7")), text(Operation::St, 7));
+        assert_eq!(None, text(Operation::Out, 0));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("jnz", Operation::Jnz.to_string());
+        assert_eq!("jmp +4", Instruction::new(Operation::Jmp, 4).to_string());
+        assert_eq!("acc -99", Instruction::new(Operation::Acc, -99).to_string());
+        assert_eq!("ld r2", Instruction::new(Operation::Ld, 2).to_string());
+        assert_eq!("in", Instruction::new(Operation::In, 0).to_string());
+        assert_eq!(
+            "jz -1  |",
+            format!("{:<7}|", Instruction::new(Operation::Jz, -1))
+        );
+    }
+}
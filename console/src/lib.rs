@@ -0,0 +1,11 @@
+pub mod analysis;
+pub mod assembler;
+pub mod cfg;
+pub mod debugger;
+pub mod disassembler;
+pub mod instruction;
+pub mod machine;
+pub mod repair;
+#[cfg(test)]
+mod test_util;
+pub mod trace;
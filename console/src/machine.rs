@@ -0,0 +1,403 @@
+use std::collections::{HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::instruction::{Instruction, Operation, Program, REGISTERS};
+
+/// Why a `Machine` stopped.
+///
+/// The machine doesn't change its state when halting, so `ip` and `acc` are
+/// still those before the faulting instruction.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Halt {
+    /// the instruction pointer is right after the last instruction
+    Terminated,
+    /// the instruction at `ip` would be executed a second time in the same
+    /// state
+    LoopDetected { ip: usize },
+    /// the jump at `ip` leads neither to an instruction nor right after the
+    /// last one, or `ip` itself is out of bounds
+    OutOfBounds { ip: usize },
+    /// the instruction at `ip` would overflow the accumulator
+    AccumulatorOverflow { ip: usize },
+    /// the instruction at `ip` reads from the empty input, the machine can
+    /// continue after more input was added
+    WaitingForInput { ip: usize },
+    /// the instruction at `ip` uses a register that doesn't exist
+    InvalidRegister { ip: usize },
+    /// a program with conditional jumps ran through the state limit of the
+    /// machine without repeating a state, it may still terminate
+    StateLimit { ip: usize },
+}
+
+impl fmt::Display for Halt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Halt::Terminated => write!(f, "terminated"),
+            Halt::LoopDetected { ip } => write!(f, "loop detected at ip {}", ip),
+            Halt::OutOfBounds { ip } => write!(f, "out of bounds at ip {}", ip),
+            Halt::AccumulatorOverflow { ip } => {
+                write!(f, "accumulator overflow at ip {}", ip)
+            }
+            Halt::WaitingForInput { ip } => write!(f, "waiting for input at ip {}", ip),
+            Halt::InvalidRegister { ip } => write!(f, "invalid register at ip {}", ip),
+            Halt::StateLimit { ip } => write!(f, "state limit reached at ip {}", ip),
+        }
+    }
+}
+
+/// How many states a machine remembers by default to detect loops in
+/// programs with conditional jumps.
+pub const DEFAULT_STATE_LIMIT: usize = 1 << 20;
+
+/// ip, accumulator, registers and number of consumed inputs
+type State = (usize, i32, [i32; REGISTERS], usize);
+
+#[derive(Debug, Clone)]
+enum LoopDetector {
+    /// Without conditional jumps the path through the program doesn't depend
+    /// on the state, so any instruction executed twice is an endless loop.
+    Executed(Vec<bool>),
+    /// With conditional jumps only a repeated state is an endless loop.
+    States(HashSet<State>),
+}
+
+/// The changes of executing a single instruction.
+struct Effect {
+    ip: usize,
+    acc: i32,
+    register: Option<(usize, i32)>,
+    output: Option<i32>,
+    consumes_input: bool,
+}
+
+/// Runs a program of the handheld console.
+///
+/// The machine halts before an endless loop would start, for programs
+/// without conditional jumps every instruction is executed at most once.
+#[derive(Debug, Clone)]
+pub struct Machine {
+    program: Program,
+    /// index of the next instruction to execute
+    pub ip: usize,
+    /// the accumulator
+    pub acc: i32,
+    pub registers: [i32; REGISTERS],
+    /// values read by `in`, front first
+    pub input: VecDeque<i32>,
+    /// values written by `out`
+    pub output: Vec<i32>,
+    consumed_input: usize,
+    loop_detector: LoopDetector,
+    /// the most states `LoopDetector::States` may hold
+    state_limit: usize,
+}
+
+impl Machine {
+    pub fn new(program: Program) -> Machine {
+        let conditional = program
+            .iter()
+            .any(|instruction| instruction.operation.opcode().conditional);
+        let loop_detector = if conditional {
+            LoopDetector::States(HashSet::new())
+        } else {
+            LoopDetector::Executed(vec![false; program.len()])
+        };
+
+        Machine {
+            program,
+            ip: 0,
+            acc: 0,
+            registers: [0; REGISTERS],
+            input: VecDeque::new(),
+            output: Vec::new(),
+            consumed_input: 0,
+            loop_detector,
+            state_limit: DEFAULT_STATE_LIMIT,
+        }
+    }
+
+    /// Halts with `Halt::StateLimit` after `state_limit` steps without a
+    /// repeated state, for programs with conditional jumps only.
+    pub fn with_state_limit(mut self, state_limit: usize) -> Machine {
+        self.state_limit = state_limit;
+        self
+    }
+
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+
+    /// Moves the instruction pointer to `ip` and starts the loop detection
+    /// over, so instructions executed before may run again.
+    pub fn jump_to(&mut self, ip: usize) {
+        self.ip = ip;
+        match &mut self.loop_detector {
+            LoopDetector::Executed(executed) => executed.iter_mut().for_each(|e| *e = false),
+            LoopDetector::States(states) => states.clear(),
+        }
+    }
+
+    fn state(&self) -> State {
+        (self.ip, self.acc, self.registers, self.consumed_input)
+    }
+
+    fn is_repeated(&self) -> bool {
+        match &self.loop_detector {
+            LoopDetector::Executed(executed) => executed[self.ip],
+            LoopDetector::States(states) => states.contains(&self.state()),
+        }
+    }
+
+    /// Executes the next instruction, or returns why the machine can't.
+    pub fn step(&mut self) -> Option<Halt> {
+        if self.ip == self.program.len() {
+            return Some(Halt::Terminated);
+        }
+        let instruction = match self.program.get(self.ip) {
+            Some(instruction) => *instruction,
+            None => return Some(Halt::OutOfBounds { ip: self.ip }),
+        };
+        if self.is_repeated() {
+            return Some(Halt::LoopDetected { ip: self.ip });
+        }
+        if let LoopDetector::States(states) = &self.loop_detector {
+            if states.len() >= self.state_limit {
+                return Some(Halt::StateLimit { ip: self.ip });
+            }
+        }
+
+        let effect = match self.execute(instruction) {
+            Ok(effect) => effect,
+            Err(halt) => return Some(halt),
+        };
+
+        match &mut self.loop_detector {
+            LoopDetector::Executed(executed) => executed[self.ip] = true,
+            LoopDetector::States(states) => {
+                let state = (self.ip, self.acc, self.registers, self.consumed_input);
+                states.insert(state);
+            }
+        }
+        self.ip = effect.ip;
+        self.acc = effect.acc;
+        if let Some((register, value)) = effect.register {
+            self.registers[register] = value;
+        }
+        if let Some(value) = effect.output {
+            self.output.push(value);
+        }
+        if effect.consumes_input {
+            self.input.pop_front();
+            self.consumed_input += 1;
+        }
+
+        None
+    }
+
+    /// what `instruction` would change, without changing anything yet
+    fn execute(&self, instruction: Instruction) -> Result<Effect, Halt> {
+        let ip = self.ip;
+        let argument = instruction.argument;
+        let mut effect = Effect {
+            ip: ip + 1,
+            acc: self.acc,
+            register: None,
+            output: None,
+            consumes_input: false,
+        };
+        let jump = |condition: bool| {
+            if !condition {
+                return Ok(ip + 1);
+            }
+            self.jump_target(argument).ok_or(Halt::OutOfBounds { ip })
+        };
+        let register = || {
+            usize::try_from(argument)
+                .ok()
+                .filter(|register| *register < REGISTERS)
+                .ok_or(Halt::InvalidRegister { ip })
+        };
+
+        match instruction.operation {
+            Operation::Nop => {}
+            Operation::Acc => {
+                effect.acc = self
+                    .acc
+                    .checked_add(argument)
+                    .ok_or(Halt::AccumulatorOverflow { ip })?
+            }
+            Operation::Jmp => effect.ip = jump(true)?,
+            Operation::Jz => effect.ip = jump(self.acc == 0)?,
+            Operation::Jnz => effect.ip = jump(self.acc != 0)?,
+            Operation::Mul => {
+                effect.acc = self
+                    .acc
+                    .checked_mul(argument)
+                    .ok_or(Halt::AccumulatorOverflow { ip })?
+            }
+            Operation::Out => effect.output = Some(self.acc),
+            Operation::In => {
+                effect.acc = *self.input.front().ok_or(Halt::WaitingForInput { ip })?;
+                effect.consumes_input = true;
+            }
+            Operation::Ld => effect.acc = self.registers[register()?],
+            Operation::St => effect.register = Some((register()?, self.acc)),
+        }
+
+        Ok(effect)
+    }
+
+    /// `None` for targets outside of the program, right after the last
+    /// instruction is still inside
+    fn jump_target(&self, offset: i32) -> Option<usize> {
+        let target = self.ip as i64 + i64::from(offset);
+
+        if target < 0 || target > self.program.len() as i64 {
+            return None;
+        }
+
+        Some(target as usize)
+    }
+
+    /// Executes instructions until the machine halts.
+    pub fn run(&mut self) -> Halt {
+        loop {
+            if let Some(halt) = self.step() {
+                return halt;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::parse_program;
+    use crate::test_util::EXAMPLE;
+
+    fn program(input: &str) -> Program {
+        parse_program(input).unwrap()
+    }
+
+    #[test]
+    fn test_loop_detected() {
+        let mut machine = Machine::new(program(EXAMPLE));
+
+        assert_eq!(Halt::LoopDetected { ip: 1 }, machine.run());
+        assert_eq!(5, machine.acc);
+        // halting doesn't change the state
+        assert_eq!(Some(Halt::LoopDetected { ip: 1 }), machine.step());
+        assert_eq!(5, machine.acc);
+    }
+
+    #[test]
+    fn test_step() {
+        let mut machine = Machine::new(program(EXAMPLE));
+
+        assert_eq!(None, machine.step());
+        assert_eq!(None, machine.step());
+        assert_eq!((2, 1), (machine.ip, machine.acc));
+        assert_eq!(None, machine.step());
+        assert_eq!((6, 1), (machine.ip, machine.acc));
+    }
+
+    #[test]
+    fn test_terminated() {
+        let mut machine = Machine::new(program(&EXAMPLE.replace("jmp -4", "nop -4")));
+
+        assert_eq!(Halt::Terminated, machine.run());
+        assert_eq!(8, machine.acc);
+        assert_eq!(9, machine.ip);
+    }
+
+    #[test]
+    fn test_jump_bounds() {
+        // right after the last instruction is a normal end
+        let mut machine = Machine::new(program("acc +1\njmp +2\nacc +1"));
+        assert_eq!(Halt::Terminated, machine.run());
+        assert_eq!((3, 1), (machine.ip, machine.acc));
+
+        let mut machine = Machine::new(program("nop +0\njmp +3\nacc +1"));
+        assert_eq!(Halt::OutOfBounds { ip: 1 }, machine.run());
+        assert_eq!(1, machine.ip);
+
+        // used to wrap around to a huge ip and count as terminated
+        let mut machine = Machine::new(program("acc +1\njmp -2"));
+        assert_eq!(Halt::OutOfBounds { ip: 1 }, machine.run());
+
+        let mut machine = Machine::new(program("jmp -2147483648"));
+        assert_eq!(Halt::OutOfBounds { ip: 0 }, machine.run());
+
+        let mut machine = Machine::new(program("nop +0"));
+        machine.ip = 5;
+        assert_eq!(Some(Halt::OutOfBounds { ip: 5 }), machine.step());
+    }
+
+    #[test]
+    fn test_accumulator_overflow() {
+        let mut machine = Machine::new(program("acc +2147483647\nacc +1"));
+        assert_eq!(Halt::AccumulatorOverflow { ip: 1 }, machine.run());
+        assert_eq!(i32::MAX, machine.acc);
+
+        let mut machine = Machine::new(program("acc -2147483648\nacc -1"));
+        assert_eq!(Halt::AccumulatorOverflow { ip: 1 }, machine.run());
+    }
+
+    #[test]
+    fn test_conditional_loop() {
+        // counts the input down to zero and writes every step
+        let countdown = "in
+out
+acc -1
+jnz -2
+st r1
+ld r1";
+        let mut machine = Machine::new(program(countdown));
+        machine.input.push_back(3);
+
+        assert_eq!(Halt::Terminated, machine.run());
+        assert_eq!(vec![3, 2, 1], machine.output);
+        assert!(machine.input.is_empty());
+
+        // the same instructions again are fine, the same state is not
+        let mut machine = Machine::new(program("jz +0"));
+        assert_eq!(Halt::LoopDetected { ip: 0 }, machine.run());
+        let mut machine = Machine::new(program("jnz +2\njmp -1"));
+        assert_eq!(Halt::LoopDetected { ip: 0 }, machine.run());
+    }
+
+    #[test]
+    fn test_state_limit() {
+        // would run until the accumulator overflows
+        let mut machine = Machine::new(program("acc +1\njnz -1")).with_state_limit(1000);
+
+        assert_eq!(Halt::StateLimit { ip: 0 }, machine.run());
+        assert_eq!(500, machine.acc);
+        assert_eq!(Some(Halt::StateLimit { ip: 0 }), machine.step());
+
+        // programs without conditional jumps remember no states
+        let mut machine = Machine::new(program("nop +0\nnop +0\nnop +0")).with_state_limit(1);
+        assert_eq!(Halt::Terminated, machine.run());
+    }
+
+    #[test]
+    fn test_registers_and_input() {
+        let mut machine = Machine::new(program("in\nst r7\nmul 3\nst r2\nld r7\nin"));
+        machine.input.push_back(5);
+
+        assert_eq!(Halt::WaitingForInput { ip: 5 }, machine.run());
+        assert_eq!((5, 5), (machine.ip, machine.acc));
+        assert_eq!([0, 0, 15, 0, 0, 0, 0, 5], machine.registers);
+
+        machine.input.push_back(-1);
+        assert_eq!(Halt::Terminated, machine.run());
+        assert_eq!(-1, machine.acc);
+
+        let mut machine = Machine::new(vec![Instruction::new(Operation::Ld, 8)]);
+        assert_eq!(Halt::InvalidRegister { ip: 0 }, machine.run());
+
+        let mut machine = Machine::new(program("acc +65536\nmul +65536"));
+        assert_eq!(Halt::AccumulatorOverflow { ip: 1 }, machine.run());
+    }
+}
@@ -0,0 +1,198 @@
+use std::fmt;
+
+use crate::cfg::{successors, ControlFlowGraph};
+use crate::instruction::{Instruction, Operation};
+use crate::machine::{Halt, Machine};
+use crate::trace::trace;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Repair {
+    /// index of the changed instruction
+    pub index: usize,
+    /// the instruction now at `index`
+    pub instruction: Instruction,
+    /// the accumulator after the repaired program terminated
+    pub acc: i32,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum RepairError {
+    AlreadyTerminates,
+    /// the path through programs with conditional jumps depends on the
+    /// state, so it can't be repaired by looking at the instructions alone
+    ConditionalJump {
+        ip: usize,
+    },
+    /// no single `jmp`/`nop` swap makes the program terminate
+    NotRepairable,
+    /// the repaired program doesn't terminate for another reason
+    Halt(Halt),
+}
+
+impl fmt::Display for RepairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepairError::AlreadyTerminates => write!(f, "the program already terminates"),
+            RepairError::ConditionalJump { ip } => {
+                write!(f, "can't repair the conditional jump at ip {}", ip)
+            }
+            RepairError::NotRepairable => {
+                write!(
+                    f,
+                    "no single swap of jmp and nop makes the program terminate"
+                )
+            }
+            RepairError::Halt(halt) => write!(f, "the repaired program stopped: {}", halt),
+        }
+    }
+}
+
+/// swaps `jmp` and `nop`, `None` for other operations
+pub fn swapped(instruction: &Instruction) -> Option<Instruction> {
+    let operation = match instruction.operation {
+        Operation::Nop => Operation::Jmp,
+        Operation::Jmp => Operation::Nop,
+        _ => return None,
+    };
+
+    Some(Instruction::new(operation, instruction.argument))
+}
+
+/// The `jmp`/`nop` swaps among the `executed` instructions that make the
+/// program terminate, in the order of `executed`.
+///
+/// `executed` are the instructions run before the program loops, only they
+/// can change where it goes. Swapping one of them helps iff its new successor
+/// leads to the end on the unchanged program, given by `reaches_end` of its
+/// control flow graph: on the way to the end the swapped instruction isn't
+/// executed again, otherwise the program would already terminate.
+pub fn terminating_swaps<'a, I>(
+    program: &'a [Instruction],
+    executed: I,
+    reaches_end: &'a [bool],
+) -> impl Iterator<Item = (usize, Instruction)> + 'a
+where
+    I: IntoIterator<Item = usize>,
+    I::IntoIter: 'a,
+{
+    executed
+        .into_iter()
+        .filter_map(move |ip| swapped(&program[ip]).map(|swapped| (ip, swapped)))
+        .filter(move |(ip, swapped)| {
+            successors(program.len(), *ip, swapped)
+                .first()
+                .is_some_and(|next| reaches_end[*next])
+        })
+}
+
+/// Finds the single `jmp`/`nop` swap that makes the program terminate.
+///
+/// The program runs once, the reverse control flow graph is searched once
+/// and the repaired program runs once, see `terminating_swaps`.
+pub fn repair(program: &[Instruction]) -> Result<Repair, RepairError> {
+    if let Some(ip) = program
+        .iter()
+        .position(|instruction| instruction.operation.opcode().conditional)
+    {
+        return Err(RepairError::ConditionalJump { ip });
+    }
+
+    let mut executed = Vec::new();
+    let halt = trace(&mut Machine::new(program.to_vec()), |entry| {
+        executed.push(entry.ip)
+    });
+    match halt {
+        Halt::Terminated => return Err(RepairError::AlreadyTerminates),
+        // the program didn't get to the loop
+        Halt::WaitingForInput { .. } | Halt::InvalidRegister { .. } => {
+            return Err(RepairError::Halt(halt))
+        }
+        // the jump leaving the program isn't traced, but swapping it may help
+        Halt::OutOfBounds { ip } if ip < program.len() => executed.push(ip),
+        _ => {}
+    }
+
+    let reaches_end = ControlFlowGraph::new(program).reaches_end();
+    let (index, instruction) = terminating_swaps(program, executed, &reaches_end)
+        .next()
+        .ok_or(RepairError::NotRepairable)?;
+
+    let mut repaired = program.to_vec();
+    repaired[index] = instruction;
+    let mut machine = Machine::new(repaired);
+    match machine.run() {
+        Halt::Terminated => Ok(Repair {
+            index,
+            instruction,
+            acc: machine.acc,
+        }),
+        halt => Err(RepairError::Halt(halt)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{Analysis, Fix};
+    use crate::assembler::parse_program;
+    use crate::test_util::EXAMPLE;
+
+    #[test]
+    fn test_repair() {
+        let program = parse_program(EXAMPLE).unwrap();
+
+        assert_eq!(
+            Ok(Repair {
+                index: 7,
+                instruction: Instruction::new(Operation::Nop, -4),
+                acc: 8
+            }),
+            repair(&program)
+        );
+    }
+
+    #[test]
+    fn test_repair_errors() {
+        let program = parse_program("nop +0\nacc +1").unwrap();
+        assert_eq!(Err(RepairError::AlreadyTerminates), repair(&program));
+
+        let program = parse_program("acc +1\njmp -1\njmp -2").unwrap();
+        assert_eq!(Err(RepairError::NotRepairable), repair(&program));
+
+        let program = parse_program("nop +0\njz -1").unwrap();
+        assert_eq!(
+            Err(RepairError::ConditionalJump { ip: 1 }),
+            repair(&program)
+        );
+
+        let program = parse_program("acc +2147483647\njmp +0\nacc +1").unwrap();
+        assert_eq!(
+            Err(RepairError::Halt(Halt::AccumulatorOverflow { ip: 2 })),
+            repair(&program)
+        );
+    }
+
+    #[test]
+    fn test_repair_out_of_bounds() {
+        let program = parse_program("nop +0\njmp -5").unwrap();
+        let fixes = Analysis::new(&program).fixes.unwrap();
+
+        let repair = repair(&program).unwrap();
+        assert_eq!(1, repair.index);
+        assert_eq!(
+            vec![Fix {
+                index: repair.index,
+                instruction: repair.instruction
+            }],
+            fixes
+        );
+    }
+
+    #[test]
+    fn test_swap_to_jump() {
+        // the first `nop` leads into a loop when swapped, the second one ends
+        let program = parse_program("nop +2\nnop +3\njmp +0\njmp -1").unwrap();
+
+        assert_eq!(1, repair(&program).unwrap().index);
+    }
+}
@@ -0,0 +1,11 @@
+/// the example program of the puzzle
+pub(crate) const EXAMPLE: &str = "nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+";
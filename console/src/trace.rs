@@ -0,0 +1,84 @@
+use std::fmt;
+
+use crate::instruction::Instruction;
+use crate::machine::{Halt, Machine};
+
+/// One executed instruction.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct TraceEntry {
+    pub ip: usize,
+    pub instruction: Instruction,
+    pub acc_before: i32,
+    pub acc_after: i32,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>5}: {}  acc {} -> {}",
+            self.ip, self.instruction, self.acc_before, self.acc_after
+        )
+    }
+}
+
+/// Executes the next instruction like `Machine::step` and records it.
+pub fn trace_step(machine: &mut Machine) -> Result<TraceEntry, Halt> {
+    let ip = machine.ip;
+    let acc_before = machine.acc;
+
+    if let Some(halt) = machine.step() {
+        return Err(halt);
+    }
+
+    Ok(TraceEntry {
+        ip,
+        instruction: machine.program()[ip],
+        acc_before,
+        acc_after: machine.acc,
+    })
+}
+
+/// Runs the machine until it halts, passing every executed instruction to
+/// `log`.
+pub fn trace<F>(machine: &mut Machine, mut log: F) -> Halt
+where
+    F: FnMut(&TraceEntry),
+{
+    loop {
+        match trace_step(machine) {
+            Ok(entry) => log(&entry),
+            Err(halt) => return halt,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::parse_program;
+
+    #[test]
+    fn test_trace() {
+        let program = parse_program("nop +0\nacc +3\njmp -2").unwrap();
+        let mut machine = Machine::new(program);
+        let mut lines = Vec::new();
+
+        let halt = trace(&mut machine, |entry| lines.push(entry.to_string()));
+
+        assert_eq!(Halt::LoopDetected { ip: 0 }, halt);
+        assert_eq!(
+            vec![
+                "    0: nop +0  acc 0 -> 0",
+                "    1: acc +3  acc 0 -> 3",
+                "    2: jmp -2  acc 3 -> 3",
+            ],
+            lines
+        );
+        assert_eq!(Err(halt), trace_step(&mut machine));
+
+        let mut machine = Machine::new(parse_program("st r1\nout").unwrap());
+        trace(&mut machine, |entry| lines.push(entry.to_string()));
+        assert_eq!("    1: out  acc 0 -> 0", lines[4]);
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::{self, BufRead};
+use std::iter::Enumerate;
+
+/// A record and where it starts in the input.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Record {
+    /// the line number of the first line, starting at 1, the other lines
+    /// follow without a gap
+    pub line: usize,
+    pub lines: Vec<String>,
+}
+
+/// Iterator over the blank-line-separated records of a reader with their
+/// line numbers.
+///
+/// Every record is the list of its lines with trailing whitespace (including a
+/// `\r` of CRLF line endings) removed. Whitespace-only lines count as blank and
+/// any number of blank lines in a row separate two records, so no empty record
+/// is ever yielded.
+pub struct NumberedRecords<R> {
+    lines: Enumerate<io::Lines<R>>,
+    lines_read: usize,
+}
+
+impl<R> NumberedRecords<R> {
+    /// the number of lines read so far, blank lines included
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+}
+
+impl<R: BufRead> Iterator for NumberedRecords<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = Record {
+            line: 0,
+            lines: Vec::new(),
+        };
+
+        for (i, line) in &mut self.lines {
+            let mut line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            self.lines_read = i + 1;
+
+            line.truncate(line.trim_end().len());
+            if !line.is_empty() {
+                if record.lines.is_empty() {
+                    record.line = i + 1;
+                }
+                record.lines.push(line);
+            } else if !record.lines.is_empty() {
+                return Some(Ok(record));
+            }
+        }
+
+        if record.lines.is_empty() {
+            None
+        } else {
+            Some(Ok(record))
+        }
+    }
+}
+
+/// Like `NumberedRecords` with just the lines of every record.
+pub struct Records<R> {
+    records: NumberedRecords<R>,
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.records
+            .next()
+            .map(|record| record.map(|record| record.lines))
+    }
+}
+
+pub trait ReadRecords: BufRead + Sized {
+    fn records(self) -> Records<Self> {
+        Records {
+            records: self.numbered_records(),
+        }
+    }
+
+    fn numbered_records(self) -> NumberedRecords<Self> {
+        NumberedRecords {
+            lines: self.lines().enumerate(),
+            lines_read: 0,
+        }
+    }
+}
+
+impl<R: BufRead> ReadRecords for R {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(input: &str) -> Vec<Vec<String>> {
+        input.as_bytes().records().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn test_records() {
+        assert_eq!(vec![vec!["a b", "c"], vec!["d"]], collect("a b\nc\n\nd\n"));
+        assert_eq!(vec![vec!["a b", "c"], vec!["d"]], collect("a b\nc\n\nd"));
+    }
+
+    #[test]
+    fn test_records_whitespace() {
+        assert_eq!(
+            vec![vec!["a", "b"], vec!["c"]],
+            collect("\r\n\na \r\nb\r\n  \r\n\n\t\nc\r\n\r\n")
+        );
+        assert!(collect("").is_empty());
+        assert!(collect("\n \n\r\n").is_empty());
+    }
+
+    #[test]
+    fn test_numbered_records() {
+        let mut records = "\na\nb\n\n\nc\n\n".as_bytes().numbered_records();
+
+        assert_eq!(
+            Record {
+                line: 2,
+                lines: vec![String::from("a"), String::from("b")]
+            },
+            records.next().unwrap().unwrap()
+        );
+        assert_eq!(6, records.next().unwrap().unwrap().line);
+        assert!(records.next().is_none());
+        assert_eq!(7, records.lines_read());
+    }
+}